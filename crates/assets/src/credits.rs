@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+
+/// who made the assets that ship with the game, and under which license.
+///
+/// this is collected from the `credits.toml` files next to the source assets,
+/// and every packed file is attributed to exactly one [`Credit`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Credits {
+    pub credits: Vec<Credit>,
+}
+
+impl Credits {
+    /// the credit for a source file, with its path relative to the asset
+    /// manifest.
+    pub fn credit(&self, file: &Path) -> Option<&Credit> {
+        self.credits.iter().find(|credit| credit.files.iter().any(|path| path == file))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Credit {
+    /// name of the asset pack or work, e.g. `Particle Pack`.
+    pub title: String,
+
+    pub author: String,
+
+    /// the license, preferably as an SPDX identifier, e.g. `CC0-1.0`.
+    pub license: String,
+
+    /// where the assets can be found.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// the packed files that this credit is for, relative to the asset
+    /// manifest.
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+}
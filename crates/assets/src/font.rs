@@ -0,0 +1,101 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Serialize, Deserialize};
+use euclid::default::Vector2D;
+
+
+/// a font that was rasterized into bitmap glyphs.
+///
+/// the glyphs are sprites in a sprite sheet, packed together with other
+/// sprites. this only has the metrics needed to lay out text with them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Font {
+    /// path of the sprite sheet with the glyphs, relative to this file.
+    pub sprite_sheet: PathBuf,
+
+    /// the font rasterized at different sizes, sorted by size.
+    pub sizes: Vec<FontSize>,
+}
+
+impl Font {
+    /// the font at exactly `size` pixels.
+    pub fn size(&self, size: u32) -> Option<&FontSize> {
+        self.sizes.iter().find(|font_size| font_size.size == size)
+    }
+
+    /// the largest size that isn't larger than `size`, or the smallest size
+    /// if all are larger.
+    pub fn closest_size(&self, size: u32) -> Option<&FontSize> {
+        self.sizes.iter()
+            .rev()
+            .find(|font_size| font_size.size <= size)
+            .or_else(|| self.sizes.first())
+    }
+}
+
+/// a font rasterized at a single size. all metrics are in pixels, with y
+/// pointing down.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FontSize {
+    /// height of the font in pixels.
+    pub size: u32,
+
+    /// distance from the baseline to the top of the highest glyph. this is
+    /// positive.
+    pub ascent: f32,
+
+    /// distance from the baseline to the bottom of the lowest glyph. this is
+    /// usually negative.
+    pub descent: f32,
+
+    /// extra space between two lines.
+    pub line_gap: f32,
+
+    /// the glyphs by character.
+    pub glyphs: BTreeMap<char, Glyph>,
+
+    /// kerning between pairs of glyphs, sorted by `left` and then `right`.
+    /// pairs that aren't listed aren't kerned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kerning: Vec<Kerning>,
+}
+
+impl FontSize {
+    /// distance between the baselines of two lines.
+    pub fn line_height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+
+    /// how much closer `right` is moved to `left`, if they're next to each
+    /// other. this is added to the advance of `left`.
+    pub fn kerning(&self, left: char, right: char) -> f32 {
+        self.kerning
+            .binary_search_by(|kerning| (kerning.left, kerning.right).cmp(&(left, right)))
+            .map_or(0.0, |i| self.kerning[i].amount)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Glyph {
+    /// name of the sprite in the sprite sheet. glyphs without any pixels, e.g.
+    /// spaces, don't have a sprite.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprite: Option<PathBuf>,
+
+    /// where the top-left corner of the sprite is drawn, relative to the pen
+    /// position on the baseline.
+    #[serde(default)]
+    pub offset: Vector2D<i32>,
+
+    /// how far the pen moves after this glyph.
+    pub advance: f32,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Kerning {
+    pub left: char,
+    pub right: char,
+
+    /// added to the advance of `left`. this is usually negative.
+    pub amount: f32,
+}
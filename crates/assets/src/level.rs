@@ -0,0 +1,186 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Serialize, Deserialize};
+use euclid::default::{Point2D, Size2D, Vector2D};
+
+
+/// a level, converted from a Tiled map.
+///
+/// tiles refer to sprites in a sprite sheet by name. the names that a level
+/// uses are listed once in [`Level::sprites`], and tiles only store an index
+/// into that list.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Level {
+    /// path of the sprite sheet with the tile sprites, relative to this file.
+    pub sprite_sheet: PathBuf,
+
+    /// size of the level in tiles.
+    pub size: Size2D<u32>,
+
+    /// size of a tile in pixels.
+    pub tile_size: Size2D<u32>,
+
+    /// keys of the sprites in the sprite sheet, that are used by tiles.
+    pub sprites: Vec<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: Properties,
+
+    /// the layers from bottom to top.
+    pub layers: Vec<Layer>,
+}
+
+impl Level {
+    /// the sprite name of a tile.
+    pub fn sprite(&self, tile: &Tile) -> Option<&PathBuf> {
+        self.sprites.get(tile.sprite as usize)
+    }
+}
+
+/// custom properties of a map, layer or object, by name.
+pub type Properties = BTreeMap<String, Property>;
+
+/// the value of a custom property. Tiled's `file` and `color` properties are
+/// just strings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Color(String),
+    File(PathBuf),
+
+    /// id of an object in the level.
+    Object(u32),
+
+    /// a custom class with its own properties.
+    Class(Properties),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TileLayer {
+    pub name: String,
+
+    /// offset of the layer in pixels.
+    #[serde(default)]
+    pub offset: Vector2D<f32>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: Properties,
+
+    /// the tiles row by row, [`Level::size`] many. `None` for empty cells.
+    pub tiles: Vec<Option<Tile>>,
+}
+
+impl TileLayer {
+    /// the tile at `x`, `y`, if `width` is the width of the level in tiles.
+    pub fn tile(&self, x: u32, y: u32, width: u32) -> Option<&Tile> {
+        self.tiles
+            .get(y as usize * width as usize + x as usize)?
+            .as_ref()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tile {
+    /// index into [`Level::sprites`].
+    pub sprite: u32,
+
+    #[serde(default, skip_serializing_if = "Flip::is_none")]
+    pub flip: Flip,
+}
+
+/// how a tile is flipped. a diagonal flip swaps x and y, which together with
+/// the other flips is used for rotated tiles.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Flip {
+    #[serde(default)]
+    pub horizontal: bool,
+
+    #[serde(default)]
+    pub vertical: bool,
+
+    #[serde(default)]
+    pub diagonal: bool,
+}
+
+impl Flip {
+    pub fn is_none(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectLayer {
+    pub name: String,
+
+    /// offset of the layer in pixels.
+    #[serde(default)]
+    pub offset: Vector2D<f32>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: Properties,
+
+    pub objects: Vec<Object>,
+}
+
+/// an object, e.g. a spawn point or a trigger area. all positions are in
+/// pixels.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Object {
+    /// unique id of the object in the level. object properties refer to this.
+    pub id: u32,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+
+    /// the class of the object, which is what the game uses to decide what to
+    /// spawn.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub class: String,
+
+    /// for tile objects this is the bottom-left corner, otherwise the top-left
+    /// corner.
+    pub position: Point2D<f32>,
+
+    #[serde(default)]
+    pub size: Size2D<f32>,
+
+    /// rotation around `position` in degrees, clockwise.
+    #[serde(default)]
+    pub rotation: f32,
+
+    #[serde(default)]
+    pub shape: Shape,
+
+    /// set for objects that are drawn as a tile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tile: Option<Tile>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: Properties,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    #[default]
+    Rectangle,
+    Ellipse,
+    Point,
+
+    /// a closed polygon. the points are relative to the object position.
+    Polygon(Vec<Point2D<f32>>),
+
+    /// an open line. the points are relative to the object position.
+    Polyline(Vec<Point2D<f32>>),
+}
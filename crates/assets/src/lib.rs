@@ -1 +1,5 @@
+pub mod credits;
+pub mod font;
+pub mod level;
+pub mod palette;
 pub mod sprite_sheet;
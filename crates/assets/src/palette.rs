@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+
+
+/// a sprite that was split into an index texture and a palette, so that it can
+/// be recolored at runtime.
+///
+/// the index texture stores the palette index of each pixel in its only
+/// channel. the palette texture has one row per variant of the sprite, and one
+/// column per palette index. recoloring a sprite is just looking up its pixels
+/// in a different row.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Palette {
+    /// path of the index texture, relative to this file.
+    pub index_texture: PathBuf,
+
+    /// path of the palette texture, relative to this file.
+    pub palette_texture: PathBuf,
+
+    /// names of the variants, i.e. the rows of the palette texture.
+    pub rows: Vec<String>,
+
+    /// number of colors in the palette, i.e. the width of the palette texture.
+    pub num_colors: usize,
+}
+
+impl Palette {
+    /// the index texture has 8 bits per pixel.
+    pub const MAX_COLORS: usize = 256;
+
+    /// index of the row with the given name.
+    pub fn row(&self, name: &str) -> Option<usize> {
+        self.rows.iter().position(|row| row == name)
+    }
+
+    /// texture coordinates of a color in the palette texture. these point at
+    /// the center of the texel, so they work with any filtering.
+    pub fn tex_coords(&self, index: u8, row: usize) -> [f32; 2] {
+        [
+            (index as f32 + 0.5) / self.num_colors as f32,
+            (row as f32 + 0.5) / self.rows.len() as f32,
+        ]
+    }
+}
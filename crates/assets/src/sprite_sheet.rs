@@ -1,17 +1,210 @@
-use std::{collections::HashMap, path::PathBuf};
+mod binary;
+
+use std::{collections::HashMap, path::{Path, PathBuf}};
 
 use serde::{Serialize, Deserialize};
-use euclid::default::Rect;
+use euclid::default::{Rect, Size2D, Vector2D};
+
+pub use self::binary::{DecodeError, EncodeError, MAGIC, VERSION};
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Sprite {
+    /// index of the atlas page (see [`SpriteSheet::pages`]) that contains
+    /// this sprite.
+    #[serde(default)]
+    pub page: usize,
+
+    /// where the sprite is in the atlas page. if the sprite was trimmed, this is
+    /// only the trimmed part. if the sprite was rotated, this is the rotated
+    /// rect, i.e. width and height are swapped.
     pub rect: Rect<u32>,
+
+    /// set if transparent borders were removed from the sprite.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim: Option<Trim>,
+
+    /// the sprite is stored rotated by 90° clockwise in the atlas page.
+    #[serde(default)]
+    pub rotated: bool,
+
+    /// set for sprites that are stretched as nine-slices, e.g. for UI panels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nine_slice: Option<NineSlice>,
+
+    /// set if the sprite was converted into a signed distance field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sdf: Option<Sdf>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Sprite {
+    /// size of the (trimmed) sprite, when it's drawn upright. this doesn't
+    /// include the border of a signed distance field.
+    pub fn size(&self) -> Size2D<u32> {
+        let size = if self.rotated {
+            Size2D::new(self.rect.size.height, self.rect.size.width)
+        }
+        else {
+            self.rect.size
+        };
+
+        let border = self.sdf.map_or(0, |sdf| sdf.spread.saturating_mul(2));
+        Size2D::new(size.width.saturating_sub(border), size.height.saturating_sub(border))
+    }
+
+    /// size of the sprite before it was trimmed.
+    pub fn original_size(&self) -> Size2D<u32> {
+        self.trim.map_or(self.size(), |trim| trim.original_size)
+    }
+
+    /// where [`Sprite::rect`] has to be drawn, relative to the top-left corner
+    /// of the untrimmed sprite.
+    pub fn offset(&self) -> Vector2D<u32> {
+        self.trim.map_or(Vector2D::zero(), |trim| trim.offset)
+    }
+
+    /// texture coordinates for the corners of the upright sprite, in the order
+    /// top-left, bottom-left, bottom-right, top-right. `page_size` is the size
+    /// of the atlas page in pixels.
+    pub fn tex_coords(&self, page_size: Size2D<u32>) -> [[f32; 2]; 4] {
+        let u = |x: u32| x as f32 / page_size.width as f32;
+        let v = |y: u32| y as f32 / page_size.height as f32;
+
+        let (left, right) = (u(self.rect.min_x()), u(self.rect.max_x()));
+        let (top, bottom) = (v(self.rect.min_y()), v(self.rect.max_y()));
+
+        if self.rotated {
+            // the top-left corner of the sprite ended up in the top-right
+            // corner of the rect.
+            [[right, top], [left, top], [left, bottom], [right, bottom]]
+        }
+        else {
+            [[left, top], [left, bottom], [right, bottom], [right, top]]
+        }
+    }
+}
+
+/// how a sprite was trimmed. with this the renderer can place a trimmed sprite
+/// as if it wasn't trimmed, so pivots relative to the original image still
+/// work.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Trim {
+    /// offset of the trimmed rect in the original image.
+    pub offset: Vector2D<u32>,
+
+    /// size of the original image.
+    pub original_size: Size2D<u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct SpriteSheet {
+    /// paths of the atlas textures, relative to the sprite sheet. the atlas is
+    /// split into multiple pages if the sprites don't fit into a single texture
+    /// of the maximum size.
+    #[serde(default)]
+    pub pages: Vec<PathBuf>,
+
+    /// paths of the mip levels of each page, relative to the sprite sheet,
+    /// starting at level 1. empty if there are no mipmaps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mip_levels: Vec<Vec<PathBuf>>,
+
+    /// paths of the normal maps of each page, relative to the sprite sheet.
+    /// they have the same size as the pages and the sprites are at the same
+    /// rects. empty if there are no normal maps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normal_pages: Vec<PathBuf>,
+
+    /// the color channels of the pages are premultiplied with alpha.
+    #[serde(default)]
+    pub premultiplied_alpha: bool,
+
     pub sprites: HashMap<PathBuf, Sprite>,
+
+    /// named animation clips.
+    #[serde(default)]
+    pub animations: HashMap<String, Animation>,
+}
+
+impl SpriteSheet {
+    /// looks up a sprite by its id.
+    pub fn sprite(&self, id: SpriteId) -> Option<&Sprite> {
+        self.sprites.get(Path::new(id.name()))
+    }
+}
+
+/// the name of a sprite, i.e. its key in [`SpriteSheet::sprites`].
+///
+/// `build-tools` can generate a module with a constant for every sprite in a
+/// sprite sheet. code that uses those constants doesn't compile anymore, if a
+/// sprite is removed or renamed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteId(&'static str);
+
+impl SpriteId {
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+/// insets of a nine-slice sprite in pixels, measured from the edges of the
+/// untrimmed sprite.
+///
+/// the corners are drawn as they are, the edges are stretched along one axis
+/// and the center is stretched along both.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NineSlice {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+/// a sprite that is stored as a signed distance field, so that it can be drawn
+/// crisply at any scale, with outlines and glow.
+///
+/// the distance to the edge of the sprite is stored in the alpha channel, the
+/// color channels are white. an alpha of 0.5 is exactly on the edge, 1.0 is
+/// `spread` pixels inside and 0.0 is `spread` pixels outside of the sprite.
+///
+/// the field extends `spread` pixels beyond the original sprite on every side,
+/// so [`Sprite::rect`] is larger than the sprite by `2 * spread`. the trim
+/// offset and nine-slice insets still refer to the original sprite.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Sdf {
+    /// how far the distance field reaches in pixels, at the size of the
+    /// original sprite.
+    pub spread: u32,
+}
+
+/// in which order the frames of an animation are played.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    #[default]
+    Forward,
+    Reverse,
+    PingPong,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// key of the sprite in [`SpriteSheet::sprites`].
+    pub sprite: PathBuf,
+
+    /// how long the frame is shown, in milliseconds.
+    pub duration: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Animation {
+    pub frames: Vec<Frame>,
+
+    #[serde(default)]
+    pub direction: Direction,
 }
 
 
@@ -0,0 +1,516 @@
+//! compact binary encoding of [`SpriteSheet`].
+//!
+//! all integers are little-endian. a file starts with [`MAGIC`] and the format
+//! [`VERSION`], followed by a table of all strings (sprite paths, page paths and
+//! animation names). everything after that refers to strings by their index in
+//! the table:
+//!
+//! ```plain
+//! magic: [u8; 4]
+//! version: u16
+//! strings: u32 count, then for each: u32 length, utf-8 bytes
+//! pages: u32 count, then for each: u32 string
+//! mip levels: u32 count, then for each page: u32 count, then for each level: u32 string
+//! normal pages: u32 count, then for each: u32 string
+//! premultiplied alpha: u8
+//! sprites: u32 count, then for each:
+//!     u32 name, u32 page, u32 x, u32 y, u32 width, u32 height, u8 flags
+//!     if flags & TRIMMED: u32 offset x, u32 offset y, u32 original width, u32 original height
+//!     if flags & NINE_SLICE: u32 left, u32 top, u32 right, u32 bottom
+//!     if flags & SDF: u32 spread
+//! animations: u32 count, then for each:
+//!     u32 name, u8 direction, u32 frame count, then for each: u32 sprite, u32 duration
+//! ```
+//!
+//! bump [`VERSION`] whenever this changes. files with a different version are
+//! rejected.
+
+use std::{collections::{BTreeMap, HashMap}, path::{Path, PathBuf}};
+
+use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use thiserror::Error;
+
+use super::{Animation, Direction, Frame, NineSlice, Sdf, Sprite, SpriteSheet, Trim};
+
+
+/// the first bytes of a binary sprite sheet.
+pub const MAGIC: [u8; 4] = *b"EHSS";
+
+/// the version of the binary format that we read and write.
+pub const VERSION: u16 = 4;
+
+const ROTATED: u8 = 1 << 0;
+const TRIMMED: u8 = 1 << 1;
+const NINE_SLICE: u8 = 1 << 2;
+const SDF: u8 = 1 << 3;
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("not a binary sprite sheet")]
+    BadMagic,
+
+    #[error("binary sprite sheet has version {version}, but only version {} is supported. rebuild the sprite sheet.", VERSION)]
+    UnsupportedVersion { version: u16 },
+
+    #[error("binary sprite sheet is truncated")]
+    UnexpectedEof,
+
+    #[error("binary sprite sheet contains an invalid string")]
+    InvalidString,
+
+    #[error("binary sprite sheet refers to string {0}, which doesn't exist")]
+    InvalidStringIndex(u32),
+
+    #[error("binary sprite sheet contains an invalid animation direction {0}")]
+    InvalidDirection(u8),
+}
+
+#[derive(Debug, Error)]
+pub enum EncodeError {
+    #[error("path `{}` isn't valid utf-8, which the binary sprite sheet format requires", .0.display())]
+    InvalidPath(PathBuf),
+}
+
+/// the paths are stored as utf-8 strings. converting them lossily would
+/// silently refer to the wrong files.
+fn path_str(path: &Path) -> Result<&str, EncodeError> {
+    path.to_str().ok_or_else(|| EncodeError::InvalidPath(path.to_owned()))
+}
+
+impl SpriteSheet {
+    /// checks if `data` looks like a binary sprite sheet.
+    pub fn is_binary(data: &[u8]) -> bool {
+        data.starts_with(&MAGIC)
+    }
+
+    /// encodes the sprite sheet in the binary format. the output only depends
+    /// on the contents, not on the iteration order of the hash maps. fails if a
+    /// path isn't valid utf-8.
+    pub fn to_binary(&self) -> Result<Vec<u8>, EncodeError> {
+        let sprites = self.sprites.iter().collect::<BTreeMap<_, _>>();
+        let animations = self.animations.iter().collect::<BTreeMap<_, _>>();
+
+        let mut strings = Strings::default();
+        for page in self.pages.iter().chain(self.mip_levels.iter().flatten()).chain(&self.normal_pages) {
+            strings.intern(path_str(page)?);
+        }
+        for name in sprites.keys() {
+            strings.intern(path_str(name)?);
+        }
+        for (name, animation) in &animations {
+            strings.intern(name);
+            for frame in &animation.frames {
+                strings.intern(path_str(&frame.sprite)?);
+            }
+        }
+
+        let mut writer = Writer::default();
+        writer.bytes(&MAGIC);
+        writer.u16(VERSION);
+
+        writer.len(strings.strings.len());
+        for string in &strings.strings {
+            writer.len(string.len());
+            writer.bytes(string.as_bytes());
+        }
+
+        writer.len(self.pages.len());
+        for page in &self.pages {
+            writer.u32(strings.index(path_str(page)?));
+        }
+
+        writer.len(self.mip_levels.len());
+        for levels in &self.mip_levels {
+            writer.len(levels.len());
+            for level in levels {
+                writer.u32(strings.index(path_str(level)?));
+            }
+        }
+
+        writer.len(self.normal_pages.len());
+        for page in &self.normal_pages {
+            writer.u32(strings.index(path_str(page)?));
+        }
+
+        writer.u8(self.premultiplied_alpha.into());
+
+        writer.len(sprites.len());
+        for (name, sprite) in sprites {
+            let mut flags = 0;
+            if sprite.rotated {
+                flags |= ROTATED;
+            }
+            if sprite.trim.is_some() {
+                flags |= TRIMMED;
+            }
+            if sprite.nine_slice.is_some() {
+                flags |= NINE_SLICE;
+            }
+            if sprite.sdf.is_some() {
+                flags |= SDF;
+            }
+
+            writer.u32(strings.index(path_str(name)?));
+            writer.len(sprite.page);
+            writer.u32(sprite.rect.origin.x);
+            writer.u32(sprite.rect.origin.y);
+            writer.u32(sprite.rect.size.width);
+            writer.u32(sprite.rect.size.height);
+            writer.u8(flags);
+
+            if let Some(trim) = sprite.trim {
+                writer.u32(trim.offset.x);
+                writer.u32(trim.offset.y);
+                writer.u32(trim.original_size.width);
+                writer.u32(trim.original_size.height);
+            }
+            if let Some(nine_slice) = sprite.nine_slice {
+                writer.u32(nine_slice.left);
+                writer.u32(nine_slice.top);
+                writer.u32(nine_slice.right);
+                writer.u32(nine_slice.bottom);
+            }
+            if let Some(sdf) = sprite.sdf {
+                writer.u32(sdf.spread);
+            }
+        }
+
+        writer.len(animations.len());
+        for (name, animation) in animations {
+            writer.u32(strings.index(name));
+            writer.u8(match animation.direction {
+                Direction::Forward => 0,
+                Direction::Reverse => 1,
+                Direction::PingPong => 2,
+            });
+            writer.len(animation.frames.len());
+            for frame in &animation.frames {
+                writer.u32(strings.index(path_str(&frame.sprite)?));
+                writer.u32(frame.duration);
+            }
+        }
+
+        Ok(writer.0)
+    }
+
+    /// decodes a sprite sheet in the binary format.
+    pub fn from_binary(data: &[u8]) -> Result<Self, DecodeError> {
+        if !Self::is_binary(data) {
+            return Err(DecodeError::BadMagic);
+        }
+        let mut reader = Reader(&data[MAGIC.len()..]);
+
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion { version });
+        }
+
+        let num_strings = reader.u32()?;
+        let mut strings = vec![];
+        for _ in 0..num_strings {
+            let len = reader.u32()? as usize;
+            let string = std::str::from_utf8(reader.bytes(len)?).map_err(|_| DecodeError::InvalidString)?;
+            strings.push(string);
+        }
+        let string = |index: u32| {
+            strings.get(index as usize).copied().ok_or(DecodeError::InvalidStringIndex(index))
+        };
+
+        let num_pages = reader.u32()?;
+        let mut pages = vec![];
+        for _ in 0..num_pages {
+            pages.push(PathBuf::from(string(reader.u32()?)?));
+        }
+
+        let num_pages = reader.u32()?;
+        let mut mip_levels = vec![];
+        for _ in 0..num_pages {
+            let num_levels = reader.u32()?;
+            let mut levels = vec![];
+            for _ in 0..num_levels {
+                levels.push(PathBuf::from(string(reader.u32()?)?));
+            }
+            mip_levels.push(levels);
+        }
+
+        let num_normal_pages = reader.u32()?;
+        let mut normal_pages = vec![];
+        for _ in 0..num_normal_pages {
+            normal_pages.push(PathBuf::from(string(reader.u32()?)?));
+        }
+
+        let premultiplied_alpha = reader.u8()? != 0;
+
+        let num_sprites = reader.u32()?;
+        let mut sprites = HashMap::new();
+        for _ in 0..num_sprites {
+            let name = PathBuf::from(string(reader.u32()?)?);
+            let page = reader.u32()? as usize;
+            let origin = Point2D::new(reader.u32()?, reader.u32()?);
+            let size = Size2D::new(reader.u32()?, reader.u32()?);
+            let rect = Rect::new(origin, size);
+            let flags = reader.u8()?;
+
+            let trim = if flags & TRIMMED != 0 {
+                Some(Trim {
+                    offset: Vector2D::new(reader.u32()?, reader.u32()?),
+                    original_size: Size2D::new(reader.u32()?, reader.u32()?),
+                })
+            }
+            else {
+                None
+            };
+            let nine_slice = if flags & NINE_SLICE != 0 {
+                Some(NineSlice {
+                    left: reader.u32()?,
+                    top: reader.u32()?,
+                    right: reader.u32()?,
+                    bottom: reader.u32()?,
+                })
+            }
+            else {
+                None
+            };
+            let sdf = if flags & SDF != 0 {
+                Some(Sdf { spread: reader.u32()? })
+            }
+            else {
+                None
+            };
+
+            sprites.insert(name, Sprite { page, rect, trim, rotated: flags & ROTATED != 0, nine_slice, sdf });
+        }
+
+        let num_animations = reader.u32()?;
+        let mut animations = HashMap::new();
+        for _ in 0..num_animations {
+            let name = string(reader.u32()?)?.to_owned();
+            let direction = match reader.u8()? {
+                0 => Direction::Forward,
+                1 => Direction::Reverse,
+                2 => Direction::PingPong,
+                direction => return Err(DecodeError::InvalidDirection(direction)),
+            };
+
+            let num_frames = reader.u32()?;
+            let mut frames = vec![];
+            for _ in 0..num_frames {
+                frames.push(Frame {
+                    sprite: PathBuf::from(string(reader.u32()?)?),
+                    duration: reader.u32()?,
+                });
+            }
+
+            animations.insert(name, Animation { frames, direction });
+        }
+
+        Ok(Self { pages, mip_levels, normal_pages, premultiplied_alpha, sprites, animations })
+    }
+}
+
+/// the string table. every string is only stored once.
+#[derive(Default)]
+struct Strings {
+    strings: Vec<String>,
+    indices: HashMap<String, u32>,
+}
+
+impl Strings {
+    fn intern(&mut self, string: &str) {
+        if !self.indices.contains_key(string) {
+            self.indices.insert(string.to_owned(), self.strings.len() as u32);
+            self.strings.push(string.to_owned());
+        }
+    }
+
+    fn index(&self, string: &str) -> u32 {
+        self.indices[string]
+    }
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len.try_into().expect("length doesn't fit into u32"));
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < len {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(page: usize, x: u32) -> Sprite {
+        Sprite {
+            page,
+            rect: Rect::new(Point2D::new(x, 2), Size2D::new(16, 8)),
+            trim: None,
+            rotated: false,
+            nine_slice: None,
+            sdf: None,
+        }
+    }
+
+    fn sprite_sheet() -> SpriteSheet {
+        let mut sprites = HashMap::new();
+        sprites.insert(PathBuf::from("plain.png"), sprite(0, 0));
+        sprites.insert(
+            PathBuf::from("trimmed.png"),
+            Sprite {
+                trim: Some(Trim { offset: Vector2D::new(1, 3), original_size: Size2D::new(20, 12) }),
+                ..sprite(0, 16)
+            },
+        );
+        sprites.insert(
+            PathBuf::from("panel.png"),
+            Sprite {
+                nine_slice: Some(NineSlice { left: 1, top: 2, right: 3, bottom: 4 }),
+                ..sprite(1, 0)
+            },
+        );
+        sprites.insert(PathBuf::from("icon.png"), Sprite { sdf: Some(Sdf { spread: 4 }), ..sprite(1, 16) });
+        sprites.insert(PathBuf::from("rotated.png"), Sprite { rotated: true, ..sprite(1, 32) });
+        sprites.insert(
+            PathBuf::from("everything.aseprite#1"),
+            Sprite {
+                trim: Some(Trim { offset: Vector2D::new(2, 0), original_size: Size2D::new(18, 8) }),
+                rotated: true,
+                nine_slice: Some(NineSlice { left: 4, top: 4, right: 4, bottom: 4 }),
+                sdf: Some(Sdf { spread: 2 }),
+                ..sprite(0, 48)
+            },
+        );
+
+        let mut animations = HashMap::new();
+        animations.insert(
+            "walk".to_owned(),
+            Animation {
+                frames: vec![
+                    Frame { sprite: PathBuf::from("plain.png"), duration: 100 },
+                    Frame { sprite: PathBuf::from("rotated.png"), duration: 150 },
+                ],
+                direction: Direction::PingPong,
+            },
+        );
+        animations.insert(
+            "blink".to_owned(),
+            Animation {
+                frames: vec![Frame { sprite: PathBuf::from("icon.png"), duration: 50 }],
+                direction: Direction::Reverse,
+            },
+        );
+
+        SpriteSheet {
+            pages: vec![PathBuf::from("atlas.0.png"), PathBuf::from("atlas.1.png")],
+            mip_levels: vec![
+                vec![PathBuf::from("atlas.0.mip1.png")],
+                vec![PathBuf::from("atlas.1.mip1.png")],
+            ],
+            normal_pages: vec![PathBuf::from("atlas.0.normal.png"), PathBuf::from("atlas.1.normal.png")],
+            premultiplied_alpha: true,
+            sprites,
+            animations,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let sprite_sheet = sprite_sheet();
+        let data = sprite_sheet.to_binary().unwrap();
+
+        assert!(SpriteSheet::is_binary(&data));
+        assert_eq!(SpriteSheet::from_binary(&data).unwrap(), sprite_sheet);
+    }
+
+    #[test]
+    fn encoding_is_deterministic() {
+        assert_eq!(sprite_sheet().to_binary().unwrap(), sprite_sheet().to_binary().unwrap());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut data = sprite_sheet().to_binary().unwrap();
+        data[0] = b'X';
+
+        assert!(matches!(SpriteSheet::from_binary(&data), Err(DecodeError::BadMagic)));
+        assert!(matches!(SpriteSheet::from_binary(b"{}"), Err(DecodeError::BadMagic)));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut data = sprite_sheet().to_binary().unwrap();
+        data[MAGIC.len()..][..2].copy_from_slice(&(VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            SpriteSheet::from_binary(&data),
+            Err(DecodeError::UnsupportedVersion { version }) if version == VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let data = sprite_sheet().to_binary().unwrap();
+
+        for len in MAGIC.len()..data.len() {
+            assert!(
+                matches!(SpriteSheet::from_binary(&data[..len]), Err(DecodeError::UnexpectedEof)),
+                "truncated to {} bytes",
+                len
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let mut sprite_sheet = sprite_sheet();
+        let path = PathBuf::from(OsStr::from_bytes(b"bad\xff.png"));
+        sprite_sheet.sprites.insert(path.clone(), sprite(0, 64));
+
+        assert!(matches!(sprite_sheet.to_binary(), Err(EncodeError::InvalidPath(invalid)) if invalid == path));
+    }
+}
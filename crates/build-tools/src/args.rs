@@ -1,23 +1,289 @@
-use std::path::PathBuf;
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    num::NonZeroU32,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
 
-use color_eyre::eyre::Error;
+use assets::sprite_sheet::NineSlice;
+use color_eyre::eyre::{
+    eyre,
+    Error,
+};
 use structopt::StructOpt;
 
+use crate::{
+    manifest::Manifest,
+    palette::Variant,
+    sprite_sheet::{
+        AtlasBuilder,
+        AtlasOptions,
+        FontOptions,
+        Grid,
+        HeightSource,
+        Input,
+        InputOptions,
+        Padding,
+        Slicing,
+    },
+    watch::{
+        self,
+        Watcher,
+    },
+};
 
 #[derive(Debug, StructOpt)]
 pub enum Args {
     Atlas {
         /// output path for the sprite sheet image
-        #[structopt(short, long)]
+        #[structopt(short = "t", long)]
         output_texture: PathBuf,
 
-        /// output path for the sprite sheet meta data.
+        /// output path for the sprite sheet meta data. use the extension `bin`
+        /// for the compact binary format, otherwise it's written as json.
+        #[structopt(short = "s", long)]
+        output_sprite_sheet: PathBuf,
+
+        /// output path for a rust module with a typed id for every sprite,
+        /// e.g. `sprites.rs`. include it with `include!`.
+        #[structopt(long)]
+        output_sprite_ids: Option<PathBuf>,
+
+        /// maximum width and height of an atlas page. if the sprites don't fit
+        /// into a single page, the atlas is split into multiple pages. the
+        /// default is the maximum texture size supported by webgl2.
+        #[structopt(long)]
+        max_page_size: Option<u32>,
+
+        /// premultiply the color channels of the atlas pages with alpha.
+        #[structopt(long)]
+        premultiply: bool,
+
+        /// also write the mip levels of every atlas page, e.g.
+        /// `atlas.mip1.png`. the mip levels average each sprite with its
+        /// padding only, but at low levels neighbouring sprites still overlap.
+        #[structopt(long)]
+        mipmaps: bool,
+
+        /// also write a normal map for every atlas page, e.g.
+        /// `atlas.normal.png`, with the same layout. the heights are guessed
+        /// from the `luminance` or the `alpha` of the sprites.
+        #[structopt(long)]
+        normal_maps: Option<HeightSource>,
+
+        /// padding around each sprite: `none`, `transparent`, `extrude` or
+        /// `tiled`, optionally followed by the width in pixels, e.g.
+        /// `extrude:2`.
+        #[structopt(long, default_value = "none")]
+        padding: Padding,
+
+        /// slice every image into tiles of this size, e.g. `70x70`. the tiles
+        /// are named `<path>#<index>`, counting row by row. fully transparent
+        /// tiles are skipped.
+        #[structopt(long, parse(try_from_str = parse_size))]
+        grid: Option<(NonZeroU32, NonZeroU32)>,
+
+        /// space around the whole grid, in pixels.
+        #[structopt(long, default_value = "0")]
+        grid_margin: u32,
+
+        /// space between neighbouring tiles of the grid, in pixels.
+        #[structopt(long, default_value = "0")]
+        grid_spacing: u32,
+
+        /// json file with named rects to slice every image into. the sprites
+        /// are named `<path>#<name>`.
+        #[structopt(long, conflicts_with = "grid")]
+        slices: Option<PathBuf>,
+
+        /// remove fully transparent borders from the sprites. the sprite sheet
+        /// records the offset and original size, so the sprites can still be
+        /// drawn as if they weren't trimmed.
+        #[structopt(long)]
+        trim: bool,
+
+        /// allow rotating sprites by 90° in the atlas, if that packs tighter.
+        /// rotated sprites are marked in the sprite sheet.
+        #[structopt(long)]
+        rotate: bool,
+
+        /// nine-slice insets for all sprites, either one value for all sides,
+        /// or `left,top,right,bottom`, e.g. `4` or `4,6,4,2`.
+        #[structopt(long, parse(try_from_str = parse_nine_slice))]
+        nine_slice: Option<NineSlice>,
+
+        /// convert all sprites into signed distance fields that reach this many
+        /// pixels beyond their edges.
+        #[structopt(long)]
+        sdf: Option<NonZeroU32>,
+
+        /// turn numbered sprites like `flame_01.png`, `flame_02.png` into an
+        /// animation clip named `flame`, showing each frame this many
+        /// milliseconds.
+        #[structopt(long)]
+        frame_duration: Option<NonZeroU32>,
+
+        /// json file with named animation clips, e.g. `{"idle": {"frames":
+        /// ["idle_a.png", "idle_b.png"], "duration": 200, "direction":
+        /// "PingPong"}}`. they replace detected clips of the same name.
+        #[structopt(long)]
+        animations: Option<PathBuf>,
+
+        /// also write overlay images that show where the sprites are in the
+        /// atlas pages, and a text report with packing statistics.
+        #[structopt(long)]
+        debug: bool,
+
+        /// don't check that every file has an attribution in a `credits.toml`
+        /// in its directory or a parent directory.
+        #[structopt(long)]
+        skip_credits: bool,
+
+        /// rebuild the atlas, even if nothing changed since the last build.
+        #[structopt(short, long)]
+        force: bool,
+
+        /// keep running and rebuild the atlas whenever an input changes.
         #[structopt(short, long)]
+        watch: bool,
+
+        /// files to put into the texture atlas. aseprite files are split into
+        /// one sprite per frame, and their tags become animation clips. fonts
+        /// are rasterized at 16 pixels, use `font` for more control.
+        files: Vec<PathBuf>,
+    },
+
+    /// rasterizes a truetype or opentype font into bitmap glyphs and packs
+    /// them into an atlas, together with any other sprites. the glyph metrics
+    /// and kerning are written to a font descriptor, e.g.
+    /// `font -t ui.png -s ui.json -d dejavu.json --sizes 12,16
+    /// fonts/dejavu.ttf sprites/ui/*.png`.
+    Font {
+        /// output path for the sprite sheet image
+        #[structopt(short = "t", long)]
+        output_texture: PathBuf,
+
+        /// output path for the sprite sheet meta data. use the extension `bin`
+        /// for the compact binary format, otherwise it's written as json.
+        #[structopt(short = "s", long)]
         output_sprite_sheet: PathBuf,
 
-        /// files to put into the texture atlas
+        /// output path for the font descriptor.
+        #[structopt(short = "d", long)]
+        output_font: PathBuf,
+
+        /// maximum width and height of an atlas page, see `atlas
+        /// --max-page-size`.
+        #[structopt(long)]
+        max_page_size: Option<u32>,
+
+        /// font sizes in pixels, e.g. `12,16,32`.
+        #[structopt(long, required = true, require_delimiter = true)]
+        sizes: Vec<u32>,
+
+        /// characters to rasterize. defaults to all printable ascii
+        /// characters.
+        #[structopt(long)]
+        chars: Option<String>,
+
+        /// padding around each glyph and sprite, see `atlas --padding`.
+        #[structopt(long, default_value = "transparent")]
+        padding: Padding,
+
+        /// don't check that every file has an attribution in a `credits.toml`
+        /// in its directory or a parent directory.
+        #[structopt(long)]
+        skip_credits: bool,
+
+        /// rebuild the atlas, even if nothing changed since the last build.
+        #[structopt(short, long)]
+        force: bool,
+
+        /// the font file (`ttf` or `otf`).
+        font: PathBuf,
+
+        /// other files to put into the texture atlas, like for `atlas`.
         files: Vec<PathBuf>,
-    }
+    },
+
+    /// splits recolored variants of a sprite into an index texture and a
+    /// palette texture with one row per variant, e.g.
+    /// `palette -i index.png -p palette.png -d palette.json dark=dark.png
+    /// light=light.png`.
+    Palette {
+        /// output path for the index texture.
+        #[structopt(short = "i", long)]
+        output_index_texture: PathBuf,
+
+        /// output path for the palette texture.
+        #[structopt(short = "p", long)]
+        output_palette_texture: PathBuf,
+
+        /// output path for the palette descriptor.
+        #[structopt(short = "d", long)]
+        output_palette: PathBuf,
+
+        /// the variants of the sprite, as `name=path` or just `path`. all
+        /// variants must have the same size. the rows of the palette are in
+        /// this order.
+        #[structopt(required = true)]
+        variants: Vec<Variant>,
+    },
+
+    /// converts a Tiled map (`tmx` or `tmj`) into a level. tiles are matched
+    /// to the sprites of a sprite sheet by their image, e.g.
+    /// `level -s atlases/platformer.json -o levels/1.json maps/1.tmx`.
+    Level {
+        /// the sprite sheet with the tiles of the map.
+        #[structopt(short = "s", long)]
+        sprite_sheet: PathBuf,
+
+        /// output path for the level.
+        #[structopt(short = "o", long)]
+        output: PathBuf,
+
+        /// rebuild the level, even if nothing changed since the last build.
+        #[structopt(short, long)]
+        force: bool,
+
+        /// the Tiled map.
+        map: PathBuf,
+    },
+
+    /// checks that sprite sheets are consistent with their atlas pages, i.e.
+    /// that all sprites are inside of their page, don't overlap, and that
+    /// their source files still exist. fails if any problems are found.
+    Validate {
+        /// the sprite sheets to check (json or binary).
+        #[structopt(required = true)]
+        sprite_sheets: Vec<PathBuf>,
+    },
+
+    /// builds everything that is configured in an asset manifest.
+    Build {
+        /// path to the manifest (toml or json).
+        #[structopt(default_value = "assets/manifest.toml")]
+        manifest: PathBuf,
+
+        /// also write overlay images that show where the sprites are in the
+        /// atlas pages, and a text report with packing statistics.
+        #[structopt(long)]
+        debug: bool,
+
+        /// rebuild everything, even if nothing changed since the last build.
+        #[structopt(short, long)]
+        force: bool,
+
+        /// keep running and rebuild the atlases whose inputs changed. if the
+        /// manifest changes, all atlases are rebuilt.
+        #[structopt(short, long)]
+        watch: bool,
+    },
 }
 
 impl Args {
@@ -26,19 +292,393 @@ impl Args {
             Args::Atlas {
                 output_texture,
                 output_sprite_sheet,
+                output_sprite_ids,
+                max_page_size,
+                premultiply,
+                mipmaps,
+                normal_maps,
+                padding,
+                grid,
+                grid_margin,
+                grid_spacing,
+                slices,
+                trim,
+                rotate,
+                nine_slice,
+                sdf,
+                frame_duration,
+                animations,
+                debug,
+                skip_credits,
+                mut force,
+                watch,
                 files,
             } => {
                 log::debug!("output_image: `{}`", output_texture.display());
                 log::debug!("output_sheet: `{}`", output_sprite_sheet.display());
-                log::debug!("output_image:");
+                log::debug!("files:");
                 for file in &files {
                     log::debug!(" - `{}`", file.display());
                 }
 
-                crate::sprite_sheet::build(output_texture, output_sprite_sheet, &files).await?;
+                let atlas_options = AtlasOptions {
+                    max_page_size: max_page_size
+                        .unwrap_or(AtlasBuilder::DEFAULT_MAX_PAGE_SIZE as u32),
+                    debug,
+                    premultiply,
+                    mipmaps,
+                    normal_maps,
+                    output_sprite_ids,
+                    animations: BTreeMap::new(),
+                };
+
+                let mut watcher = watch.then(Watcher::new).transpose()?;
+                if let Some(watcher) = &mut watcher {
+                    for path in files.iter().chain(&slices).chain(&animations) {
+                        watcher.watch(watch::pattern_dir(path))?;
+                    }
+                }
+
+                loop {
+                    let result = async {
+                        // the slices are loaded on every build, so that changes to them are
+                        // picked up in watch mode.
+                        let slicing = if let Some((tile_width, tile_height)) = grid {
+                            Slicing::Grid(Grid {
+                                tile_width,
+                                tile_height,
+                                margin: grid_margin,
+                                spacing: grid_spacing,
+                            })
+                        }
+                        else if let Some(slices) = &slices {
+                            let json = tokio::fs::read_to_string(slices).await?;
+                            Slicing::Rects(serde_json::from_str(&json)?)
+                        }
+                        else {
+                            Slicing::None
+                        };
+
+                        let atlas_options = if let Some(animations) = &animations {
+                            let json = tokio::fs::read_to_string(animations).await?;
+                            AtlasOptions {
+                                animations: serde_json::from_str(&json)?,
+                                ..atlas_options.clone()
+                            }
+                        }
+                        else {
+                            atlas_options.clone()
+                        };
+
+                        let options = InputOptions {
+                            padding,
+                            slicing,
+                            trim,
+                            rotate,
+                            nine_slice,
+                            sdf,
+                            font: FontOptions::default(),
+                            frame_duration,
+                        };
+                        // checked on every build, so that changes to the
+                        // `credits.toml` files are picked up in watch mode.
+                        if !skip_credits {
+                            let files = files.iter().cloned().collect();
+                            crate::credits::check(Path::new(""), &files).await?;
+                        }
+
+                        let inputs = files
+                            .iter()
+                            .map(|path| Input::new(path.clone(), options.clone()))
+                            .collect::<Vec<_>>();
+
+                        crate::sprite_sheet::build(
+                            &output_texture,
+                            &output_sprite_sheet,
+                            &atlas_options,
+                            &inputs,
+                            force,
+                        )
+                        .await?;
+
+                        Ok(())
+                    }
+                    .await;
+
+                    let watcher = if let Some(watcher) = &mut watcher {
+                        watcher
+                    }
+                    else {
+                        return result;
+                    };
+                    log_error(result);
+
+                    watcher.changes().await;
+                    force = false;
+                }
+            }
+            Args::Font {
+                output_texture,
+                output_sprite_sheet,
+                output_font,
+                max_page_size,
+                sizes,
+                chars,
+                padding,
+                skip_credits,
+                force,
+                font,
+                files,
+            } => {
+                if !skip_credits {
+                    let files = files.iter().chain([&font]).cloned().collect();
+                    crate::credits::check(Path::new(""), &files).await?;
+                }
+
+                let atlas_options = AtlasOptions {
+                    max_page_size: max_page_size
+                        .unwrap_or(AtlasBuilder::DEFAULT_MAX_PAGE_SIZE as u32),
+                    debug: false,
+                    premultiply: false,
+                    mipmaps: false,
+                    normal_maps: None,
+                    output_sprite_ids: None,
+                    animations: BTreeMap::new(),
+                };
+                let options = InputOptions {
+                    padding,
+                    ..Default::default()
+                };
+                let font = Input::new(
+                    font,
+                    InputOptions {
+                        font: FontOptions {
+                            sizes,
+                            chars: chars.unwrap_or_else(FontOptions::default_chars),
+                        },
+                        ..options.clone()
+                    },
+                );
+                let inputs = files
+                    .into_iter()
+                    .map(|path| Input::new(path, options.clone()))
+                    .collect::<Vec<_>>();
+
+                crate::font::build(
+                    &output_texture,
+                    &output_sprite_sheet,
+                    &output_font,
+                    &atlas_options,
+                    font,
+                    &inputs,
+                    force,
+                )
+                .await?;
+
+                Ok(())
+            }
+            Args::Palette {
+                output_index_texture,
+                output_palette_texture,
+                output_palette,
+                variants,
+            } => {
+                crate::palette::build(
+                    output_index_texture,
+                    output_palette_texture,
+                    output_palette,
+                    &variants,
+                )
+                .await?;
+
+                Ok(())
+            }
+            Args::Level {
+                sprite_sheet,
+                output,
+                force,
+                map,
+            } => {
+                crate::level::build(&map, &sprite_sheet, &output, force).await?;
+
+                Ok(())
+            }
+            Args::Validate { sprite_sheets } => {
+                let mut num_problems = 0;
+
+                for path in &sprite_sheets {
+                    let problems = crate::validate::validate(path).await?;
+
+                    if problems.is_empty() {
+                        log::info!("`{}` is valid", path.display());
+                    }
+                    else {
+                        log::error!("`{}` has {} problems:", path.display(), problems.len());
+                        for problem in &problems {
+                            log::error!(" - {}", problem);
+                        }
+                    }
+
+                    num_problems += problems.len();
+                }
+
+                if num_problems == 0 {
+                    Ok(())
+                }
+                else {
+                    Err(eyre!("found {} problems in sprite sheets", num_problems))
+                }
+            }
+            Args::Build {
+                manifest,
+                debug,
+                mut force,
+                watch,
+            } => {
+                let mut watcher = watch.then(Watcher::new).transpose()?;
+                if let Some(watcher) = &mut watcher {
+                    watcher.watch(watch::pattern_dir(&manifest))?;
+                }
+
+                // changed paths since the last build. on the first build
+                // everything is built.
+                let mut changes = None;
+
+                loop {
+                    let result =
+                        build_manifest(&manifest, changes.as_ref(), debug, force, watcher.as_mut())
+                            .await;
+
+                    let watcher = if let Some(watcher) = &mut watcher {
+                        watcher
+                    }
+                    else {
+                        return result;
+                    };
+                    log_error(result);
+
+                    changes = Some(watcher.changes().await);
+                    force = false;
+                }
+            }
+        }
+    }
+}
+
+/// builds the atlases in a manifest. if `changes` is set, only the atlases
+/// with changed inputs are built, unless the manifest itself changed. the
+/// input directories of all atlases are added to the `watcher`.
+async fn build_manifest(
+    path: &Path,
+    changes: Option<&BTreeSet<PathBuf>>,
+    debug: bool,
+    force: bool,
+    mut watcher: Option<&mut Watcher>,
+) -> Result<(), Error> {
+    let manifest = Manifest::load(path).await?;
+
+    let changes = match (changes, path.canonicalize()) {
+        (Some(changes), Ok(path)) if !changes.contains(&path) => Some(changes),
+        _ => None,
+    };
+
+    if let Some(output_credits) = &manifest.output_credits {
+        let mut files = BTreeSet::new();
+        for atlas in manifest.atlases.values() {
+            files.extend(manifest.inputs(atlas)?.into_iter().map(|input| input.path));
+        }
+
+        // before any atlas is packed, so that unattributed files never end up
+        // in one.
+        crate::credits::build(
+            &manifest.base_path,
+            &files,
+            manifest.path(output_credits),
+            force,
+        )
+        .await?;
+    }
+
+    for (name, atlas) in &manifest.atlases {
+        let input_dirs = manifest.input_dirs(atlas);
+
+        if let Some(watcher) = &mut watcher {
+            for dir in &input_dirs {
+                watcher.watch(dir)?;
             }
         }
 
-        Ok(())
+        if changes.is_none_or(|changes| watch::any_changed(changes, &input_dirs)) {
+            log::debug!("atlas `{}`", name);
+
+            let inputs = manifest.inputs(atlas)?;
+            let options = AtlasOptions {
+                max_page_size: atlas.max_page_size,
+                debug,
+                premultiply: atlas.premultiply,
+                mipmaps: atlas.mipmaps,
+                normal_maps: atlas.normal_maps,
+                output_sprite_ids: atlas
+                    .output_sprite_ids
+                    .as_ref()
+                    .map(|path| manifest.path(path)),
+                animations: atlas.animations.clone(),
+            };
+
+            crate::sprite_sheet::build(
+                manifest.path(&atlas.output_texture),
+                manifest.path(&atlas.output_sprite_sheet),
+                &options,
+                &inputs,
+                force,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// in watch mode errors are only logged, so that we keep watching.
+fn log_error(result: Result<(), Error>) {
+    if let Err(e) = result {
+        log::error!("{}", e);
+    }
+}
+
+/// parses a size like `70x70`.
+fn parse_size(s: &str) -> Result<(NonZeroU32, NonZeroU32), String> {
+    let error = || format!("invalid size `{}`, expected e.g. `70x70`", s);
+    let parse = |n: &str| n.parse().ok().ok_or_else(error);
+    let (width, height) = s.split_once('x').ok_or_else(error)?;
+    Ok((parse(width)?, parse(height)?))
+}
+
+/// parses nine-slice insets like `4` or `4,6,4,2`.
+fn parse_nine_slice(s: &str) -> Result<NineSlice, String> {
+    let error = || format!("invalid nine-slice insets `{}`, expected e.g. `4,6,4,2`", s);
+    let insets = s
+        .split(',')
+        .map(|n| n.trim().parse::<u32>().map_err(|_| error()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match insets[..] {
+        [n] => {
+            Ok(NineSlice {
+                left: n,
+                top: n,
+                right: n,
+                bottom: n,
+            })
+        }
+        [left, top, right, bottom] => {
+            Ok(NineSlice {
+                left,
+                top,
+                right,
+                bottom,
+            })
+        }
+        _ => Err(error()),
     }
 }
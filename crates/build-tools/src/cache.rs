@@ -0,0 +1,254 @@
+//! cache for incremental builds.
+//!
+//! next to every output we keep a cache file with the hashes of all inputs
+//! and a fingerprint of the options that were used to build it. if neither
+//! changed and the outputs still exist, the output doesn't need to be rebuilt.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// hash of some data, e.g. an input file.
+pub type Hash = u128;
+
+pub fn hash(data: &[u8]) -> Hash {
+    utils::file_hash::murmur3_x64_128(data, Cache::HASH_SEED)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    /// hash of the options and the version of the build tools.
+    options: Hash,
+
+    /// the directory the build tools ran in. relative input and output paths
+    /// are relative to it.
+    #[serde(default)]
+    dir: PathBuf,
+
+    /// hashes of the input files.
+    inputs: BTreeMap<PathBuf, Hash>,
+
+    /// files that were written.
+    outputs: Vec<PathBuf>,
+}
+
+impl Cache {
+    pub const HASH_SEED: u32 = 1312;
+
+    /// version of the build tools' output. bump this with every change that
+    /// changes the output for the same inputs and options, e.g. a fix to the
+    /// mipmaps or padding, so that outputs of older versions are rebuilt.
+    pub const VERSION: u32 = 2;
+
+    /// creates a cache for a build. `options` is everything that changes the
+    /// output, apart from the input files. we fingerprint it by its debug
+    /// representation, together with [`Cache::VERSION`] and the version of the
+    /// binary sprite sheet format.
+    pub fn new(options: &impl Debug) -> Self {
+        let options = format!(
+            "{}:{}:{:?}",
+            Self::VERSION,
+            assets::sprite_sheet::VERSION,
+            options
+        );
+
+        Self {
+            options: hash(options.as_bytes()),
+            dir: std::env::current_dir().unwrap_or_default(),
+            inputs: BTreeMap::new(),
+            outputs: vec![],
+        }
+    }
+
+    pub fn add_input(&mut self, path: PathBuf, data: &[u8]) {
+        self.inputs.insert(path, hash(data));
+    }
+
+    pub fn add_output(&mut self, path: PathBuf) {
+        self.outputs.push(path);
+    }
+
+    /// paths of the input files, resolved against the directory of the build,
+    /// so that they don't depend on the current directory.
+    pub fn inputs(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.inputs.keys().map(|path| self.dir.join(path))
+    }
+
+    /// path of the cache file for an output, e.g. `atlas.json.cache.json`. the
+    /// extension of the output is kept, so that outputs that only differ in
+    /// their extension, like `atlas.json` and `atlas.bin`, have their own
+    /// caches.
+    pub fn path(output: &Path) -> PathBuf {
+        let mut path = output.as_os_str().to_owned();
+        path.push(".cache.json");
+        path.into()
+    }
+
+    /// loads the cache for `output`. returns `None` if there is no cache file,
+    /// or it can't be read.
+    pub async fn load(output: &Path) -> Option<Self> {
+        let json = tokio::fs::read(Self::path(output)).await.ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+
+    pub async fn save(&self, output: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_vec_pretty(self)?;
+        tokio::fs::write(Self::path(output), json).await
+    }
+
+    /// whether `output` has to be rebuilt, because something changed since the
+    /// last build, or `force` is set. the reasons are logged.
+    pub async fn needs_rebuild(&self, output: &Path, force: bool) -> bool {
+        let mut changes = self.changes(Self::load(output).await.as_ref());
+        if force {
+            changes.push("rebuild was forced".to_owned());
+        }
+        if changes.is_empty() {
+            log::info!("`{}` is up to date", output.display());
+            return false;
+        }
+
+        log::info!("building `{}`:", output.display());
+        for change in &changes {
+            log::info!(" - {}", change);
+        }
+        true
+    }
+
+    /// reasons why the output has to be rebuilt, if `previous` is the cache of
+    /// the last build. if this is empty, the output is up to date.
+    pub fn changes(&self, previous: Option<&Cache>) -> Vec<String> {
+        let previous = if let Some(previous) = previous {
+            previous
+        }
+        else {
+            return vec!["not built yet".to_owned()];
+        };
+
+        let mut changes = vec![];
+
+        if self.options != previous.options {
+            changes.push("options changed".to_owned());
+        }
+
+        for (path, hash) in &self.inputs {
+            match previous.inputs.get(path) {
+                None => changes.push(format!("`{}` was added", path.display())),
+                Some(previous_hash) if previous_hash != hash => {
+                    changes.push(format!("`{}` changed", path.display()))
+                }
+                _ => {}
+            }
+        }
+
+        for path in previous.inputs.keys() {
+            if !self.inputs.contains_key(path) {
+                changes.push(format!("`{}` was removed", path.display()));
+            }
+        }
+
+        for path in &previous.outputs {
+            if !path.exists() {
+                changes.push(format!("output `{}` is missing", path.display()));
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(options: &str, inputs: &[(&str, &[u8])]) -> Cache {
+        let mut cache = Cache::new(&options);
+        for (path, data) in inputs {
+            cache.add_input(path.into(), data);
+        }
+        cache
+    }
+
+    #[test]
+    fn up_to_date() {
+        let previous = cache("options", &[("a.png", b"a"), ("b.png", b"b")]);
+        let current = cache("options", &[("b.png", b"b"), ("a.png", b"a")]);
+
+        assert!(current.changes(Some(&previous)).is_empty());
+    }
+
+    #[test]
+    fn not_built_yet() {
+        let current = cache("options", &[]);
+
+        assert_eq!(current.changes(None), ["not built yet"]);
+    }
+
+    #[test]
+    fn options_changed() {
+        let previous = cache("options", &[("a.png", b"a")]);
+        let current = cache("other options", &[("a.png", b"a")]);
+
+        assert_eq!(current.changes(Some(&previous)), ["options changed"]);
+    }
+
+    #[test]
+    fn inputs_changed() {
+        let previous = cache("options", &[("a.png", b"a"), ("b.png", b"b")]);
+        let current = cache("options", &[("a.png", b"changed"), ("c.png", b"c")]);
+
+        assert_eq!(
+            current.changes(Some(&previous)),
+            [
+                "`a.png` changed",
+                "`c.png` was added",
+                "`b.png` was removed"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_outputs() {
+        let existing = std::env::temp_dir();
+        let missing = existing.join("build-tools-cache-test/missing.png");
+
+        let mut previous = cache("options", &[]);
+        previous.add_output(existing);
+        previous.add_output(missing.clone());
+        let current = cache("options", &[]);
+
+        assert_eq!(
+            current.changes(Some(&previous)),
+            [format!("output `{}` is missing", missing.display())]
+        );
+    }
+
+    #[test]
+    fn path() {
+        assert_eq!(
+            Cache::path(Path::new("atlases/atlas.json")),
+            Path::new("atlases/atlas.json.cache.json")
+        );
+    }
+
+    #[test]
+    fn inputs_are_relative_to_the_build_directory() {
+        let mut previous = cache("options", &[("a.png", b"a")]);
+        previous.dir = PathBuf::from("/project");
+
+        assert_eq!(
+            previous.inputs().collect::<Vec<_>>(),
+            [Path::new("/project/a.png")]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! attribution for the source assets.
+//!
+//! a `credits.toml` in a directory says who made the files in it, and under
+//! which license:
+//!
+//! ```toml
+//! [[credit]]
+//! title = "Particle Pack"
+//! author = "Kenney"
+//! license = "CC0-1.0"
+//! url = "https://example.com/particle-pack"
+//! ```
+//!
+//! `url` is optional. a credit can be limited to some files with
+//! `files = ["*.aseprite"]`, with patterns relative to the directory.
+//! otherwise it's for all files in the directory and its subdirectories.
+//!
+//! a packed file is attributed to the first matching credit in the closest
+//! `credits.toml`, looking up to the directory of the asset manifest. if a
+//! file has no attribution, the build fails before anything is packed.
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+    },
+    io::ErrorKind,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::credits::{
+    Credit,
+    Credits,
+};
+use glob::{
+    MatchOptions,
+    Pattern,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::cache::Cache;
+
+pub const FILE_NAME: &str = "credits.toml";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to load `{path}`: {source}")]
+    Toml {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("invalid pattern in `{path}`: {source}")]
+    Pattern {
+        path: PathBuf,
+        #[source]
+        source: glob::PatternError,
+    },
+
+    #[error("{num_files} files have no attribution in a `credits.toml`")]
+    MissingAttribution { num_files: usize },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreditsFile {
+    #[serde(default, rename = "credit")]
+    credits: Vec<CreditEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreditEntry {
+    title: String,
+    author: String,
+    license: String,
+    url: Option<String>,
+
+    /// patterns for the files this is for, relative to the directory. if
+    /// empty, it's for all files.
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+/// a loaded `credits.toml`, with the patterns compiled.
+struct Entries {
+    entries: Vec<(CreditEntry, Vec<Pattern>)>,
+}
+
+impl Entries {
+    /// the index of the first entry that is for `file`, with the path relative
+    /// to the directory of the `credits.toml`.
+    fn find(&self, file: &Path) -> Option<usize> {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        self.entries.iter().position(|(_, patterns)| {
+            patterns.is_empty()
+                || patterns
+                    .iter()
+                    .any(|pattern| pattern.matches_path_with(file, options))
+        })
+    }
+}
+
+async fn load(path: &Path, cache: &mut Cache) -> Result<Option<Entries>, Error> {
+    let data = match tokio::fs::read(path).await {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    cache.add_input(path.to_owned(), &data);
+
+    let file: CreditsFile = toml::from_str(&String::from_utf8_lossy(&data)).map_err(|source| {
+        Error::Toml {
+            path: path.to_owned(),
+            source,
+        }
+    })?;
+
+    let entries = file
+        .credits
+        .into_iter()
+        .map(|entry| {
+            let patterns = entry
+                .files
+                .iter()
+                .map(|pattern| Pattern::new(pattern))
+                .collect::<Result<_, _>>()
+                .map_err(|source| {
+                    Error::Pattern {
+                        path: path.to_owned(),
+                        source,
+                    }
+                })?;
+            Ok((entry, patterns))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(Some(Entries { entries }))
+}
+
+/// collects the credits for `files`, relative to `base_path`. the
+/// `credits.toml` files that are read are added to `cache`. fails if any file
+/// has no attribution.
+async fn collect(
+    base_path: &Path,
+    files: &BTreeSet<PathBuf>,
+    cache: &mut Cache,
+) -> Result<Credits, Error> {
+    // `credits.toml` files by directory, `None` if there is none.
+    let mut credits_files = HashMap::new();
+    // the files of every credit, by directory and index in the file.
+    let mut credited = BTreeMap::<(PathBuf, usize), Vec<PathBuf>>::new();
+    let mut num_missing = 0;
+
+    for file in files {
+        let mut found = None;
+
+        for dir in file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(base_path))
+        {
+            if !credits_files.contains_key(dir) {
+                let entries = load(&dir.join(FILE_NAME), cache).await?;
+                credits_files.insert(dir.to_owned(), entries);
+            }
+
+            let entries = credits_files[dir].as_ref();
+            // `ancestors` only yields prefixes of `file`.
+            let relative = file.strip_prefix(dir).unwrap();
+            if let Some(index) = entries.and_then(|entries| entries.find(relative)) {
+                found = Some((dir.to_owned(), index));
+                break;
+            }
+        }
+
+        if let Some(credit) = found {
+            let relative = file.strip_prefix(base_path).unwrap_or(file);
+            credited
+                .entry(credit)
+                .or_default()
+                .push(relative.to_owned());
+        }
+        else {
+            log::error!("`{}` has no attribution", file.display());
+            num_missing += 1;
+        }
+    }
+
+    if num_missing > 0 {
+        return Err(Error::MissingAttribution {
+            num_files: num_missing,
+        });
+    }
+
+    Ok(Credits {
+        credits: credited
+            .into_iter()
+            .map(|((dir, index), files)| {
+                let entries = credits_files[&dir].as_ref().unwrap();
+                let (entry, _) = &entries.entries[index];
+
+                Credit {
+                    title: entry.title.clone(),
+                    author: entry.author.clone(),
+                    license: entry.license.clone(),
+                    url: entry.url.clone(),
+                    files,
+                }
+            })
+            .collect(),
+    })
+}
+
+/// checks that all `files` have an attribution, without writing anything.
+/// this is done before packing them, so that unattributed files never end up
+/// in an atlas.
+pub async fn check(base_path: &Path, files: &BTreeSet<PathBuf>) -> Result<(), Error> {
+    collect(base_path, files, &mut Cache::default()).await?;
+    Ok(())
+}
+
+/// collects the credits for `files`, which are all files that are packed into
+/// any atlas, and writes them to `output`. `base_path` is the directory of the
+/// asset manifest. the files in the credits are relative to it.
+///
+/// unless `force` is set, nothing is written if neither the files nor any
+/// `credits.toml` changed since the last build.
+pub async fn build(
+    base_path: &Path,
+    files: &BTreeSet<PathBuf>,
+    output: impl AsRef<Path>,
+    force: bool,
+) -> Result<(), Error> {
+    let output = output.as_ref();
+    let mut cache = Cache::new(files);
+    let credits = collect(base_path, files, &mut cache).await?;
+
+    if !cache.needs_rebuild(output, force).await {
+        return Ok(());
+    }
+
+    log::info!(
+        "credited {} files in {} credits",
+        files.len(),
+        credits.credits.len()
+    );
+
+    let json = serde_json::to_vec_pretty(&credits)?;
+    tokio::fs::write(output, json).await?;
+    cache.add_output(output.to_owned());
+    cache.save(output).await?;
+
+    Ok(())
+}
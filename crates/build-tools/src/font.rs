@@ -0,0 +1,81 @@
+//! bitmap font atlases.
+//!
+//! a font is rasterized at fixed sizes and its glyphs are packed into an atlas,
+//! together with any other sprites. the metrics and kerning are written to a
+//! font descriptor, which refers to the glyph sprites in the sprite sheet.
+
+use std::path::Path;
+
+use assets::font::Font;
+use thiserror::Error;
+
+use crate::{
+    paths,
+    sprite_sheet::{
+        self,
+        AtlasOptions,
+        Input,
+    },
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    SpriteSheet(#[from] sprite_sheet::Error),
+}
+
+/// builds the atlas with the glyphs of `font` and all other `inputs`, and
+/// writes the font descriptor to `output_font`. the sizes and characters are
+/// taken from the options of the font input.
+pub async fn build(
+    output_texture: impl AsRef<Path>,
+    output_sprite_sheet: impl AsRef<Path>,
+    output_font: impl AsRef<Path>,
+    options: &AtlasOptions,
+    font: Input,
+    inputs: &[Input],
+    force: bool,
+) -> Result<(), Error> {
+    let output_sprite_sheet = output_sprite_sheet.as_ref();
+    let output_font = output_font.as_ref();
+
+    let mut all_inputs = Vec::with_capacity(inputs.len() + 1);
+    all_inputs.push(font.clone());
+    all_inputs.extend_from_slice(inputs);
+
+    sprite_sheet::build(
+        output_texture,
+        output_sprite_sheet,
+        options,
+        &all_inputs,
+        force,
+    )
+    .await?;
+
+    // the metrics are cheap to compute, so the descriptor is always written,
+    // even if the atlas was up to date.
+    let data = tokio::fs::read(&font.path).await?;
+    let bitmap_font = sprite_sheet::font::load(&font.path, &font.name, &data, &font.options.font)?;
+
+    // the sprite sheet path is relative to the descriptor.
+    let descriptor = Font {
+        sprite_sheet: paths::relative_to_file(output_sprite_sheet, output_font),
+        sizes: bitmap_font.sizes,
+    };
+
+    log::info!(
+        "writing font with {} sizes to `{}`",
+        descriptor.sizes.len(),
+        output_font.display()
+    );
+    let json = serde_json::to_vec_pretty(&descriptor)?;
+    tokio::fs::write(output_font, json).await?;
+
+    Ok(())
+}
@@ -0,0 +1,711 @@
+//! converts Tiled maps into levels.
+//!
+//! maps can be either xml (`.tmx`) or json (`.tmj`), and so can their external
+//! tilesets (`.tsx` or `.tsj`). tiles are resolved to sprites in a sprite sheet
+//! by their image:
+//!
+//! - for tilesets that are a collection of images, the image of a tile is the
+//!   sprite.
+//! - for tilesets that are a single image, the tile is the sprite
+//!   `<image>#<tile id>`, which is how the atlas names the tiles when the image
+//!   is sliced with a grid of the same tile size.
+//!
+//! sprites are named after their path relative to some directory (see
+//! `strip_prefix` in the manifest), so a sprite matches an image if the image
+//! path ends with the sprite name. if multiple sprites match, the longest name
+//! wins.
+
+mod tiled;
+mod tmx;
+
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::{
+    level::{
+        Flip,
+        Layer,
+        Level,
+        Object,
+        ObjectLayer,
+        Shape,
+        Tile,
+        TileLayer,
+    },
+    sprite_sheet::SpriteSheet,
+};
+use euclid::default::{
+    Point2D,
+    Size2D,
+    Vector2D,
+};
+use thiserror::Error;
+
+use crate::{
+    cache::Cache,
+    paths,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("{0}")]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("{0}")]
+    SpriteSheet(#[from] crate::sprite_sheet::LoadError),
+
+    #[error("`{path}` is not a Tiled map or tileset: expected `tmx`, `tmj`, `tsx` or `tsj`")]
+    UnknownFormat { path: PathBuf },
+
+    #[error("invalid map `{path}`: {reason}")]
+    InvalidMap { path: PathBuf, reason: String },
+
+    #[error("map `{path}` is infinite, which isn't supported")]
+    InfiniteMap { path: PathBuf },
+
+    #[error("unsupported tile layer compression `{0}`")]
+    UnsupportedCompression(String),
+
+    #[error("tile layer `{layer}` has {num_tiles} tiles, but the map has {expected} cells")]
+    TileCount {
+        layer: String,
+        num_tiles: usize,
+        expected: usize,
+    },
+
+    #[error("tile {gid} isn't in any tileset")]
+    InvalidGid { gid: u32 },
+
+    #[error("tile {tile} of tileset `{tileset}` doesn't have an image")]
+    MissingImage { tileset: String, tile: u32 },
+
+    #[error("no sprite in the sprite sheet matches `{image}`")]
+    UnresolvedTile { image: String },
+}
+
+/// the gid flags for flipped tiles. the lowest bits are the tile id.
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+
+fn extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|extension| extension.to_str())
+}
+
+/// loads a map and its external tilesets. all image paths in the tilesets are
+/// resolved relative to the working directory. the paths of the external
+/// tilesets are added to `cache`.
+async fn load_map(path: &Path, cache: &mut Cache) -> Result<tiled::Map, Error> {
+    let data = tokio::fs::read(path).await?;
+    cache.add_input(path.to_owned(), &data);
+
+    let mut map = match extension(path) {
+        Some("tmx") => tmx::parse_map(path, &String::from_utf8_lossy(&data))?,
+        Some("tmj" | "json") => serde_json::from_slice(&data)?,
+        _ => {
+            return Err(Error::UnknownFormat {
+                path: path.to_owned(),
+            })
+        }
+    };
+
+    if map.infinite {
+        return Err(Error::InfiniteMap {
+            path: path.to_owned(),
+        });
+    }
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for tileset in &mut map.tilesets {
+        let mut tileset_dir = dir.to_owned();
+
+        if let Some(source) = &tileset.source {
+            let source = dir.join(source);
+            let data = tokio::fs::read(&source).await?;
+            cache.add_input(source.clone(), &data);
+
+            let external = match extension(&source) {
+                Some("tsx") => tmx::parse_tileset(&source, &String::from_utf8_lossy(&data))?,
+                Some("tsj" | "json") => serde_json::from_slice(&data)?,
+                _ => return Err(Error::UnknownFormat { path: source }),
+            };
+
+            tileset.image = external.image;
+            tileset.tiles = external.tiles;
+            tileset_dir = source.parent().unwrap_or_else(|| Path::new("")).to_owned();
+        }
+
+        if let Some(image) = &mut tileset.image {
+            *image = tileset_dir.join(&*image);
+        }
+        for tile in &mut tileset.tiles {
+            if let Some(image) = &mut tile.image {
+                *image = tileset_dir.join(&*image);
+            }
+        }
+    }
+
+    // look up tilesets from the back, so they need to be sorted.
+    map.tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+    Ok(map)
+}
+
+/// turns global tile ids into tiles.
+struct Resolver<'a> {
+    tilesets: &'a [tiled::Tileset],
+
+    /// sprites in the sprite sheet, split into the path and the part after
+    /// `#`.
+    sprites: Vec<(&'a Path, Option<&'a str>, &'a Path)>,
+
+    /// the sprites used by the level, and their indices.
+    used: Vec<PathBuf>,
+    indices: HashMap<u32, u32>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(tilesets: &'a [tiled::Tileset], sprite_sheet: &'a SpriteSheet) -> Self {
+        let sprites = sprite_sheet
+            .sprites
+            .keys()
+            .map(|name| {
+                let (path, suffix) = match name.to_str().and_then(|name| name.split_once('#')) {
+                    Some((path, suffix)) => (Path::new(path), Some(suffix)),
+                    None => (name.as_path(), None),
+                };
+                (path, suffix, name.as_path())
+            })
+            .collect();
+
+        Self {
+            tilesets,
+            sprites,
+            used: vec![],
+            indices: HashMap::new(),
+        }
+    }
+
+    /// resolves a gid. returns `None` for empty cells.
+    fn tile(&mut self, gid: u32) -> Result<Option<Tile>, Error> {
+        let flags =
+            FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL;
+        let id = gid & !flags;
+        if id == 0 {
+            return Ok(None);
+        }
+
+        let sprite = if let Some(sprite) = self.indices.get(&id) {
+            *sprite
+        }
+        else {
+            let name = self.sprite_name(id)?.to_owned();
+            let sprite = self.used.len() as u32;
+            self.used.push(name);
+            self.indices.insert(id, sprite);
+            sprite
+        };
+
+        Ok(Some(Tile {
+            sprite,
+            flip: Flip {
+                horizontal: gid & FLIPPED_HORIZONTALLY != 0,
+                vertical: gid & FLIPPED_VERTICALLY != 0,
+                diagonal: gid & FLIPPED_DIAGONALLY != 0,
+            },
+        }))
+    }
+
+    /// finds the sprite for a tile id without flags.
+    fn sprite_name(&self, id: u32) -> Result<&'a Path, Error> {
+        let tileset = self
+            .tilesets
+            .iter()
+            .rev()
+            .find(|tileset| tileset.first_gid <= id)
+            .ok_or(Error::InvalidGid { gid: id })?;
+        let local_id = id - tileset.first_gid;
+
+        let tile_image = tileset
+            .tiles
+            .iter()
+            .find(|tile| tile.id == local_id)
+            .and_then(|tile| tile.image.as_deref());
+
+        let (image, suffix) = match (tile_image, &tileset.image) {
+            (Some(image), _) => (image, None),
+            (None, Some(image)) => (image.as_path(), Some(local_id.to_string())),
+            (None, None) => {
+                return Err(Error::MissingImage {
+                    tileset: tileset.source.as_ref().map_or_else(
+                        || tileset.first_gid.to_string(),
+                        |source| source.display().to_string(),
+                    ),
+                    tile: local_id,
+                })
+            }
+        };
+
+        self.sprites
+            .iter()
+            .filter(|(path, sprite_suffix, _)| {
+                image.ends_with(path) && *sprite_suffix == suffix.as_deref()
+            })
+            .max_by_key(|(path, _, _)| path.components().count())
+            .map(|(_, _, name)| *name)
+            .ok_or_else(|| {
+                Error::UnresolvedTile {
+                    image: match suffix {
+                        Some(suffix) => format!("{}#{}", image.display(), suffix),
+                        None => image.display().to_string(),
+                    },
+                }
+            })
+    }
+}
+
+/// converts the layers of a map. group layers are flattened, their names are
+/// prepended to the names of their children, e.g. `background/clouds`.
+fn convert_layers(
+    layers: Vec<tiled::Layer>,
+    prefix: &str,
+    offset: Vector2D<f32>,
+    num_cells: usize,
+    resolver: &mut Resolver,
+    output: &mut Vec<Layer>,
+) -> Result<(), Error> {
+    for layer in layers {
+        match layer {
+            tiled::Layer::TileLayer(layer) => {
+                let gids = layer.gids()?;
+                let info = layer.info;
+                let name = format!("{}{}", prefix, info.name);
+
+                if gids.len() != num_cells {
+                    return Err(Error::TileCount {
+                        layer: name,
+                        num_tiles: gids.len(),
+                        expected: num_cells,
+                    });
+                }
+
+                output.push(Layer::Tiles(TileLayer {
+                    name,
+                    offset: offset + Vector2D::new(info.offset_x, info.offset_y),
+                    properties: info.properties,
+                    tiles: gids
+                        .into_iter()
+                        .map(|gid| resolver.tile(gid))
+                        .collect::<Result<_, _>>()?,
+                }));
+            }
+            tiled::Layer::ObjectGroup(group) => {
+                let info = group.info;
+                let objects = group
+                    .objects
+                    .into_iter()
+                    .map(|object| convert_object(object, resolver))
+                    .collect::<Result<_, _>>()?;
+
+                output.push(Layer::Objects(ObjectLayer {
+                    name: format!("{}{}", prefix, info.name),
+                    offset: offset + Vector2D::new(info.offset_x, info.offset_y),
+                    properties: info.properties,
+                    objects,
+                }));
+            }
+            tiled::Layer::Group(group) => {
+                let info = group.info;
+                convert_layers(
+                    group.layers,
+                    &format!("{}{}/", prefix, info.name),
+                    offset + Vector2D::new(info.offset_x, info.offset_y),
+                    num_cells,
+                    resolver,
+                    output,
+                )?;
+            }
+            tiled::Layer::ImageLayer(layer) => {
+                log::warn!("skipping image layer `{}{}`", prefix, layer.info.name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn convert_object(object: tiled::Object, resolver: &mut Resolver) -> Result<Object, Error> {
+    let points = |points: Vec<tiled::Point>| {
+        points
+            .into_iter()
+            .map(|point| Point2D::new(point.x, point.y))
+            .collect()
+    };
+
+    let shape = if let Some(polygon) = object.polygon {
+        Shape::Polygon(points(polygon))
+    }
+    else if let Some(polyline) = object.polyline {
+        Shape::Polyline(points(polyline))
+    }
+    else if object.point {
+        Shape::Point
+    }
+    else if object.ellipse {
+        Shape::Ellipse
+    }
+    else {
+        Shape::Rectangle
+    };
+
+    Ok(Object {
+        id: object.id,
+        name: object.name,
+        class: object.class,
+        position: Point2D::new(object.x, object.y),
+        size: Size2D::new(object.width, object.height),
+        rotation: object.rotation,
+        shape,
+        tile: object
+            .gid
+            .map(|gid| resolver.tile(gid))
+            .transpose()?
+            .flatten(),
+        properties: object.properties,
+    })
+}
+
+/// converts the Tiled map at `map` into a level, with the tiles resolved
+/// against the sprite sheet at `sprite_sheet`, and writes it to `output`.
+///
+/// unless `force` is set, nothing is done if neither the map, its tilesets, nor
+/// the sprite sheet changed since the last build.
+pub async fn build(
+    map_path: impl AsRef<Path>,
+    sprite_sheet_path: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    force: bool,
+) -> Result<(), Error> {
+    let map_path = map_path.as_ref();
+    let sprite_sheet_path = sprite_sheet_path.as_ref();
+    let output = output.as_ref();
+
+    let mut cache = Cache::new(&(map_path, sprite_sheet_path, output));
+    let map = load_map(map_path, &mut cache).await?;
+    cache.add_input(
+        sprite_sheet_path.to_owned(),
+        &tokio::fs::read(sprite_sheet_path).await?,
+    );
+
+    if !cache.needs_rebuild(output, force).await {
+        return Ok(());
+    }
+
+    let sprite_sheet = crate::sprite_sheet::load(sprite_sheet_path).await?;
+    let mut resolver = Resolver::new(&map.tilesets, &sprite_sheet);
+
+    let num_cells = (map.width as usize)
+        .checked_mul(map.height as usize)
+        .ok_or_else(|| {
+            Error::InvalidMap {
+                path: map_path.to_owned(),
+                reason: format!("the map is too large ({}x{})", map.width, map.height),
+            }
+        })?;
+
+    let mut layers = vec![];
+    convert_layers(
+        map.layers,
+        "",
+        Vector2D::zero(),
+        num_cells,
+        &mut resolver,
+        &mut layers,
+    )?;
+
+    // the sprite sheet path is relative to the level.
+    let level = Level {
+        sprite_sheet: paths::relative_to_file(sprite_sheet_path, output),
+        size: Size2D::new(map.width, map.height),
+        tile_size: Size2D::new(map.tile_width, map.tile_height),
+        sprites: resolver.used,
+        properties: map.properties,
+        layers,
+    };
+    log::info!(
+        "converted {} layers using {} sprites",
+        level.layers.len(),
+        level.sprites.len()
+    );
+
+    let json = serde_json::to_vec_pretty(&level)?;
+    tokio::fs::write(output, json).await?;
+    cache.add_output(output.to_owned());
+    cache.save(output).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assets::sprite_sheet::Sprite;
+    use euclid::default::Rect;
+
+    use super::*;
+
+    fn sprite_sheet(names: &[&str]) -> SpriteSheet {
+        SpriteSheet {
+            pages: vec![PathBuf::from("atlas.png")],
+            mip_levels: vec![],
+            normal_pages: vec![],
+            premultiplied_alpha: false,
+            sprites: names
+                .iter()
+                .map(|name| {
+                    let sprite = Sprite {
+                        page: 0,
+                        rect: Rect::new(Point2D::origin(), Size2D::new(16, 16)),
+                        trim: None,
+                        rotated: false,
+                        nine_slice: None,
+                        sdf: None,
+                    };
+                    (PathBuf::from(name), sprite)
+                })
+                .collect(),
+            animations: HashMap::new(),
+        }
+    }
+
+    /// an image collection tileset at gid 1, and a single image tileset at
+    /// gid 10.
+    fn tilesets() -> Vec<tiled::Tileset> {
+        vec![
+            tiled::Tileset {
+                first_gid: 1,
+                tiles: vec![
+                    tiled::TilesetTile {
+                        id: 0,
+                        image: Some(PathBuf::from("assets/sprites/props/crate.png")),
+                    },
+                    tiled::TilesetTile {
+                        id: 2,
+                        image: Some(PathBuf::from("assets/sprites/props/barrel.png")),
+                    },
+                ],
+                ..Default::default()
+            },
+            tiled::Tileset {
+                first_gid: 10,
+                image: Some(PathBuf::from("assets/sprites/platformer/tiles.png")),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn tile(sprite: u32) -> Option<Tile> {
+        Some(Tile {
+            sprite,
+            flip: Flip::default(),
+        })
+    }
+
+    #[test]
+    fn multiple_tilesets() {
+        let tilesets = tilesets();
+        let sprite_sheet = sprite_sheet(&[
+            "props/crate.png",
+            "props/barrel.png",
+            "platformer/tiles.png#0",
+            "platformer/tiles.png#5",
+        ]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        assert_eq!(resolver.tile(0).unwrap(), None);
+        assert_eq!(resolver.tile(3).unwrap(), tile(0));
+        assert_eq!(resolver.tile(15).unwrap(), tile(1));
+        assert_eq!(resolver.tile(1).unwrap(), tile(2));
+        assert_eq!(resolver.tile(10).unwrap(), tile(3));
+        // sprites that are used again keep their index.
+        assert_eq!(resolver.tile(15).unwrap(), tile(1));
+
+        assert_eq!(
+            resolver.used,
+            [
+                Path::new("props/barrel.png"),
+                Path::new("platformer/tiles.png#5"),
+                Path::new("props/crate.png"),
+                Path::new("platformer/tiles.png#0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn flip_flags() {
+        let tilesets = tilesets();
+        let sprite_sheet = sprite_sheet(&["props/crate.png"]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        let mut flip = |gid| resolver.tile(gid).unwrap().unwrap();
+        assert_eq!(flip(1), tile(0).unwrap());
+        assert_eq!(
+            flip(1 | FLIPPED_HORIZONTALLY),
+            Tile {
+                sprite: 0,
+                flip: Flip {
+                    horizontal: true,
+                    vertical: false,
+                    diagonal: false,
+                },
+            }
+        );
+        assert_eq!(
+            flip(1 | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY),
+            Tile {
+                sprite: 0,
+                flip: Flip {
+                    horizontal: false,
+                    vertical: true,
+                    diagonal: true,
+                },
+            }
+        );
+        // the hexagonal rotation flag isn't supported, but it's not part of the
+        // tile id either.
+        assert_eq!(flip(1 | ROTATED_HEXAGONAL), tile(0).unwrap());
+
+        // flags without a tile are still an empty cell.
+        assert_eq!(resolver.tile(FLIPPED_HORIZONTALLY).unwrap(), None);
+        assert_eq!(resolver.used, [Path::new("props/crate.png")]);
+    }
+
+    #[test]
+    fn longest_sprite_name_wins() {
+        let tilesets = tilesets();
+        let sprite_sheet = sprite_sheet(&["crate.png", "props/crate.png", "sprites/crate.png"]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        resolver.tile(1).unwrap();
+        assert_eq!(resolver.used, [Path::new("props/crate.png")]);
+    }
+
+    #[test]
+    fn unresolved_tiles() {
+        let tilesets = tilesets();
+        let sprite_sheet = sprite_sheet(&["props/crate.png", "platformer/tiles.png#0"]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        assert!(matches!(
+            resolver.tile(3),
+            Err(Error::UnresolvedTile { image }) if image == "assets/sprites/props/barrel.png"
+        ));
+        assert!(matches!(
+            resolver.tile(11),
+            Err(Error::UnresolvedTile { image }) if image == "assets/sprites/platformer/tiles.png#1"
+        ));
+        // tile 1 of the first tileset has no image.
+        assert!(matches!(
+            resolver.tile(2),
+            Err(Error::MissingImage { tile: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn gid_before_the_first_tileset() {
+        let mut tilesets = tilesets();
+        tilesets.remove(0);
+        let sprite_sheet = sprite_sheet(&[]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        assert!(matches!(
+            resolver.tile(3),
+            Err(Error::InvalidGid { gid: 3 })
+        ));
+    }
+
+    #[test]
+    fn layers_are_flattened() {
+        let tilesets = tilesets();
+        let sprite_sheet = sprite_sheet(&["props/crate.png"]);
+        let mut resolver = Resolver::new(&tilesets, &sprite_sheet);
+
+        let tile_layer = |name: &str, gids: Vec<u32>| {
+            tiled::Layer::TileLayer(tiled::TileLayer {
+                info: tiled::LayerInfo {
+                    name: name.to_owned(),
+                    offset_x: 1.0,
+                    ..Default::default()
+                },
+                data: tiled::Data::Gids(gids),
+                compression: None,
+            })
+        };
+        let layers = vec![
+            tile_layer("ground", vec![0, 1]),
+            tiled::Layer::Group(tiled::Group {
+                info: tiled::LayerInfo {
+                    name: "background".to_owned(),
+                    offset_y: 2.0,
+                    ..Default::default()
+                },
+                layers: vec![tile_layer("clouds", vec![1, 0])],
+            }),
+        ];
+
+        let mut output = vec![];
+        convert_layers(layers, "", Vector2D::zero(), 2, &mut resolver, &mut output).unwrap();
+
+        let tile_layers = output
+            .iter()
+            .map(|layer| {
+                match layer {
+                    Layer::Tiles(layer) => (layer.name.as_str(), layer.offset, &layer.tiles[..]),
+                    Layer::Objects(_) => panic!("unexpected object layer"),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tile_layers,
+            [
+                ("ground", Vector2D::new(1.0, 0.0), &[None, tile(0)][..]),
+                (
+                    "background/clouds",
+                    Vector2D::new(1.0, 2.0),
+                    &[tile(0), None][..]
+                ),
+            ]
+        );
+
+        let result = convert_layers(
+            vec![tile_layer("short", vec![1])],
+            "",
+            Vector2D::zero(),
+            2,
+            &mut resolver,
+            &mut vec![],
+        );
+        assert!(matches!(
+            result,
+            Err(Error::TileCount {
+                num_tiles: 1,
+                expected: 2,
+                ..
+            })
+        ));
+    }
+}
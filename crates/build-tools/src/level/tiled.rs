@@ -0,0 +1,292 @@
+//! the parts of a Tiled map that we care about.
+//!
+//! this follows the json format (`.tmj`), so json maps are just deserialized.
+//! xml maps (`.tmx`) are converted into the same structs, see [`super::tmx`].
+//!
+//! see https://doc.mapeditor.org/en/stable/reference/json-map-format/
+
+use std::{
+    io::Read,
+    path::PathBuf,
+};
+
+use assets::level::{
+    Properties,
+    Property,
+};
+use base64::Engine;
+use flate2::read::{
+    GzDecoder,
+    ZlibDecoder,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+};
+
+use super::Error;
+
+#[derive(Debug, Deserialize)]
+pub struct Map {
+    pub width: u32,
+    pub height: u32,
+
+    #[serde(rename = "tilewidth")]
+    pub tile_width: u32,
+
+    #[serde(rename = "tileheight")]
+    pub tile_height: u32,
+
+    #[serde(default)]
+    pub infinite: bool,
+
+    #[serde(default, deserialize_with = "deserialize_properties")]
+    pub properties: Properties,
+
+    #[serde(default)]
+    pub tilesets: Vec<Tileset>,
+
+    pub layers: Vec<Layer>,
+}
+
+/// a tileset of a map. external tilesets are loaded when the map is loaded,
+/// so this always has the tiles.
+#[derive(Debug, Default, Deserialize)]
+pub struct Tileset {
+    /// the global tile id of the first tile in this tileset.
+    #[serde(rename = "firstgid", default)]
+    pub first_gid: u32,
+
+    /// path of an external tileset, relative to the map.
+    pub source: Option<PathBuf>,
+
+    /// the image for tilesets that are a single image. paths are relative to
+    /// the map or tileset file until the map is loaded, then they're relative
+    /// to the working directory.
+    pub image: Option<PathBuf>,
+
+    /// tiles with their own image, for image collection tilesets.
+    #[serde(default)]
+    pub tiles: Vec<TilesetTile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TilesetTile {
+    pub id: u32,
+    pub image: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Layer {
+    TileLayer(TileLayer),
+    ObjectGroup(ObjectGroup),
+    Group(Group),
+    ImageLayer(ImageLayer),
+}
+
+/// offset and properties, which all layers have.
+#[derive(Debug, Default, Deserialize)]
+pub struct LayerInfo {
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(rename = "offsetx", default)]
+    pub offset_x: f32,
+
+    #[serde(rename = "offsety", default)]
+    pub offset_y: f32,
+
+    #[serde(default, deserialize_with = "deserialize_properties")]
+    pub properties: Properties,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TileLayer {
+    #[serde(flatten)]
+    pub info: LayerInfo,
+
+    /// csv data is just an array of gids in json.
+    pub data: Data,
+
+    /// `zlib`, `gzip` or `zstd`, if the data is base64 encoded.
+    pub compression: Option<String>,
+}
+
+/// the global tile ids of a tile layer.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Data {
+    Gids(Vec<u32>),
+
+    /// base64 encoded and maybe compressed.
+    Encoded(String),
+}
+
+impl TileLayer {
+    /// decodes the global tile ids of the layer.
+    pub fn gids(&self) -> Result<Vec<u32>, Error> {
+        let encoded = match &self.data {
+            Data::Gids(gids) => return Ok(gids.clone()),
+            Data::Encoded(encoded) => encoded,
+        };
+
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded.trim())?;
+        let bytes = match self.compression.as_deref() {
+            None | Some("") => bytes,
+            Some("zlib") => decompress(ZlibDecoder::new(&bytes[..]))?,
+            Some("gzip") => decompress(GzDecoder::new(&bytes[..]))?,
+            Some(compression) => return Err(Error::UnsupportedCompression(compression.to_owned())),
+        };
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|gid| u32::from_le_bytes(gid.try_into().unwrap()))
+            .collect())
+    }
+}
+
+fn decompress(mut decoder: impl Read) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![];
+    decoder.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ObjectGroup {
+    #[serde(flatten)]
+    pub info: LayerInfo,
+
+    pub objects: Vec<Object>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Group {
+    #[serde(flatten)]
+    pub info: LayerInfo,
+
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageLayer {
+    #[serde(flatten)]
+    pub info: LayerInfo,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Object {
+    pub id: u32,
+
+    #[serde(default)]
+    pub name: String,
+
+    /// called `type` before Tiled 1.9, and `class` in 1.9.
+    #[serde(rename = "type", alias = "class", default)]
+    pub class: String,
+
+    pub x: f32,
+    pub y: f32,
+
+    #[serde(default)]
+    pub width: f32,
+
+    #[serde(default)]
+    pub height: f32,
+
+    #[serde(default)]
+    pub rotation: f32,
+
+    /// set for tile objects.
+    pub gid: Option<u32>,
+
+    #[serde(default)]
+    pub point: bool,
+
+    #[serde(default)]
+    pub ellipse: bool,
+
+    pub polygon: Option<Vec<Point>>,
+
+    pub polyline: Option<Vec<Point>>,
+
+    #[serde(default, deserialize_with = "deserialize_properties")]
+    pub properties: Properties,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// a property as it's stored in json maps.
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+
+    #[serde(rename = "type", default = "JsonProperty::default_type")]
+    property_type: String,
+
+    value: serde_json::Value,
+}
+
+impl JsonProperty {
+    fn default_type() -> String {
+        "string".to_owned()
+    }
+}
+
+fn deserialize_properties<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Properties, D::Error> {
+    Vec::<JsonProperty>::deserialize(deserializer)?
+        .into_iter()
+        .map(|property| {
+            let value =
+                json_property(&property.property_type, property.value).ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "invalid value for property `{}`",
+                        property.name
+                    ))
+                })?;
+            Ok((property.name, value))
+        })
+        .collect()
+}
+
+/// converts the value of a json property. the members of class properties
+/// don't have a type, so it's guessed from the json value.
+fn json_property(property_type: &str, value: serde_json::Value) -> Option<Property> {
+    use serde_json::Value;
+
+    let property = match (property_type, value) {
+        ("bool", Value::Bool(value)) => Property::Bool(value),
+        ("int", Value::Number(value)) => Property::Int(value.as_i64()?),
+        ("float", Value::Number(value)) => Property::Float(value.as_f64()?),
+        ("string", Value::String(value)) => Property::String(value),
+        ("color", Value::String(value)) => Property::Color(value),
+        ("file", Value::String(value)) => Property::File(value.into()),
+        ("object", Value::Number(value)) => Property::Object(value.as_u64()?.try_into().ok()?),
+        ("class", Value::Object(members)) => {
+            Property::Class(
+                members
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let property_type = match &value {
+                            Value::Bool(_) => "bool",
+                            Value::Number(number) if number.is_i64() => "int",
+                            Value::Number(_) => "float",
+                            Value::Object(_) => "class",
+                            _ => "string",
+                        };
+                        Some((name, json_property(property_type, value)?))
+                    })
+                    .collect::<Option<_>>()?,
+            )
+        }
+        _ => return None,
+    };
+
+    Some(property)
+}
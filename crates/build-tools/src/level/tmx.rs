@@ -0,0 +1,539 @@
+//! reads Tiled's xml formats (`.tmx` maps and `.tsx` tilesets) into the
+//! structs of the json format.
+//!
+//! see https://doc.mapeditor.org/en/stable/reference/tmx-map-format/
+
+use std::{
+    path::Path,
+    str::FromStr,
+};
+
+use assets::level::{
+    Properties,
+    Property,
+};
+use roxmltree::{
+    Document,
+    Node,
+};
+
+use super::{
+    tiled::{
+        Data,
+        Group,
+        ImageLayer,
+        Layer,
+        LayerInfo,
+        Map,
+        Object,
+        ObjectGroup,
+        Point,
+        TileLayer,
+        Tileset,
+        TilesetTile,
+    },
+    Error,
+};
+
+/// parses a `.tmx` map.
+pub fn parse_map(path: &Path, xml: &str) -> Result<Map, Error> {
+    let document = Document::parse(xml)?;
+    let parser = Parser { path };
+    parser.map(document.root_element())
+}
+
+/// parses a `.tsx` tileset. the first gid isn't part of the file, so it's 0.
+pub fn parse_tileset(path: &Path, xml: &str) -> Result<Tileset, Error> {
+    let document = Document::parse(xml)?;
+    let parser = Parser { path };
+    parser.tileset(document.root_element())
+}
+
+/// remembers the path of the file for error messages.
+struct Parser<'a> {
+    path: &'a Path,
+}
+
+impl<'a> Parser<'a> {
+    fn invalid(&self, reason: impl Into<String>) -> Error {
+        Error::InvalidMap {
+            path: self.path.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    /// parses an optional attribute.
+    fn attribute<T: FromStr>(&self, node: Node, name: &str) -> Result<Option<T>, Error> {
+        node.attribute(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    self.invalid(format!(
+                        "invalid attribute `{}` on `<{}>`: `{}`",
+                        name,
+                        node.tag_name().name(),
+                        value
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// parses a required attribute.
+    fn required<T: FromStr>(&self, node: Node, name: &str) -> Result<T, Error> {
+        self.attribute(node, name)?.ok_or_else(|| {
+            self.invalid(format!(
+                "missing attribute `{}` on `<{}>`",
+                name,
+                node.tag_name().name()
+            ))
+        })
+    }
+
+    fn map(&self, node: Node) -> Result<Map, Error> {
+        if !node.has_tag_name("map") {
+            return Err(self.invalid("expected `<map>`"));
+        }
+
+        Ok(Map {
+            width: self.required(node, "width")?,
+            height: self.required(node, "height")?,
+            tile_width: self.required(node, "tilewidth")?,
+            tile_height: self.required(node, "tileheight")?,
+            infinite: self.attribute::<u8>(node, "infinite")?.unwrap_or(0) != 0,
+            properties: self.properties(node)?,
+            tilesets: node
+                .children()
+                .filter(|child| child.has_tag_name("tileset"))
+                .map(|child| self.tileset(child))
+                .collect::<Result<_, _>>()?,
+            layers: self.layers(node)?,
+        })
+    }
+
+    fn tileset(&self, node: Node) -> Result<Tileset, Error> {
+        let image = node
+            .children()
+            .find(|child| child.has_tag_name("image"))
+            .map(|image| self.required(image, "source"))
+            .transpose()?;
+
+        let mut tiles = vec![];
+        for tile in node.children().filter(|child| child.has_tag_name("tile")) {
+            tiles.push(TilesetTile {
+                id: self.required(tile, "id")?,
+                image: tile
+                    .children()
+                    .find(|child| child.has_tag_name("image"))
+                    .map(|image| self.required(image, "source"))
+                    .transpose()?,
+            });
+        }
+
+        Ok(Tileset {
+            first_gid: self.attribute(node, "firstgid")?.unwrap_or(0),
+            source: self.attribute(node, "source")?,
+            image,
+            tiles,
+        })
+    }
+
+    /// the layers that are children of `node`.
+    fn layers(&self, node: Node) -> Result<Vec<Layer>, Error> {
+        let mut layers = vec![];
+
+        for child in node.children() {
+            let layer = match child.tag_name().name() {
+                "layer" => Layer::TileLayer(self.tile_layer(child)?),
+                "objectgroup" => {
+                    Layer::ObjectGroup(ObjectGroup {
+                        info: self.layer_info(child)?,
+                        objects: child
+                            .children()
+                            .filter(|child| child.has_tag_name("object"))
+                            .map(|child| self.object(child))
+                            .collect::<Result<_, _>>()?,
+                    })
+                }
+                "group" => {
+                    Layer::Group(Group {
+                        info: self.layer_info(child)?,
+                        layers: self.layers(child)?,
+                    })
+                }
+                "imagelayer" => {
+                    Layer::ImageLayer(ImageLayer {
+                        info: self.layer_info(child)?,
+                    })
+                }
+                _ => continue,
+            };
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
+
+    fn layer_info(&self, node: Node) -> Result<LayerInfo, Error> {
+        Ok(LayerInfo {
+            name: node.attribute("name").unwrap_or_default().to_owned(),
+            offset_x: self.attribute(node, "offsetx")?.unwrap_or(0.0),
+            offset_y: self.attribute(node, "offsety")?.unwrap_or(0.0),
+            properties: self.properties(node)?,
+        })
+    }
+
+    fn tile_layer(&self, node: Node) -> Result<TileLayer, Error> {
+        let data = node
+            .children()
+            .find(|child| child.has_tag_name("data"))
+            .ok_or_else(|| self.invalid("`<layer>` without `<data>`"))?;
+        let text = data.text().unwrap_or_default();
+
+        let gids = match data.attribute("encoding") {
+            Some("base64") => Data::Encoded(text.to_owned()),
+            Some("csv") => {
+                Data::Gids(
+                    text.split(',')
+                        .map(|gid| {
+                            gid.trim()
+                                .parse()
+                                .map_err(|_| self.invalid(format!("invalid tile `{}`", gid.trim())))
+                        })
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            // the deprecated format with one `<tile>` element per tile.
+            None => {
+                Data::Gids(
+                    data.children()
+                        .filter(|child| child.has_tag_name("tile"))
+                        .map(|tile| Ok(self.attribute(tile, "gid")?.unwrap_or(0)))
+                        .collect::<Result<_, Error>>()?,
+                )
+            }
+            Some(encoding) => return Err(self.invalid(format!("unknown encoding `{}`", encoding))),
+        };
+
+        Ok(TileLayer {
+            info: self.layer_info(node)?,
+            data: gids,
+            compression: data.attribute("compression").map(str::to_owned),
+        })
+    }
+
+    fn object(&self, node: Node) -> Result<Object, Error> {
+        let child = |name: &str| node.children().find(|child| child.has_tag_name(name));
+
+        Ok(Object {
+            id: self.required(node, "id")?,
+            name: node.attribute("name").unwrap_or_default().to_owned(),
+            class: node
+                .attribute("type")
+                .or_else(|| node.attribute("class"))
+                .unwrap_or_default()
+                .to_owned(),
+            x: self.attribute(node, "x")?.unwrap_or(0.0),
+            y: self.attribute(node, "y")?.unwrap_or(0.0),
+            width: self.attribute(node, "width")?.unwrap_or(0.0),
+            height: self.attribute(node, "height")?.unwrap_or(0.0),
+            rotation: self.attribute(node, "rotation")?.unwrap_or(0.0),
+            gid: self.attribute(node, "gid")?,
+            point: child("point").is_some(),
+            ellipse: child("ellipse").is_some(),
+            polygon: child("polygon")
+                .map(|polygon| self.points(polygon))
+                .transpose()?,
+            polyline: child("polyline")
+                .map(|polyline| self.points(polyline))
+                .transpose()?,
+            properties: self.properties(node)?,
+        })
+    }
+
+    /// parses the `points` attribute of a polygon or polyline, e.g.
+    /// `0,0 16,0 16,16`.
+    fn points(&self, node: Node) -> Result<Vec<Point>, Error> {
+        let points: String = self.required(node, "points")?;
+
+        points
+            .split_whitespace()
+            .map(|point| {
+                point
+                    .split_once(',')
+                    .and_then(|(x, y)| {
+                        Some(Point {
+                            x: x.parse().ok()?,
+                            y: y.parse().ok()?,
+                        })
+                    })
+                    .ok_or_else(|| self.invalid(format!("invalid point `{}`", point)))
+            })
+            .collect()
+    }
+
+    fn property_value<T: FromStr>(&self, name: &str, value: &str) -> Result<T, Error> {
+        value.parse().map_err(|_| {
+            self.invalid(format!(
+                "invalid value for property `{}`: `{}`",
+                name, value
+            ))
+        })
+    }
+
+    /// the properties in the `<properties>` child of `node`.
+    fn properties(&self, node: Node) -> Result<Properties, Error> {
+        let mut properties = Properties::new();
+
+        let children = node
+            .children()
+            .filter(|child| child.has_tag_name("properties"))
+            .flat_map(|properties| properties.children())
+            .filter(|child| child.has_tag_name("property"));
+
+        for property in children {
+            let name: String = self.required(property, "name")?;
+            // multi-line strings are stored as text instead of an attribute.
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default();
+            let value = match property.attribute("type").unwrap_or("string") {
+                "bool" => Property::Bool(self.property_value(&name, value)?),
+                "int" => Property::Int(self.property_value(&name, value)?),
+                "float" => Property::Float(self.property_value(&name, value)?),
+                "color" => Property::Color(value.to_owned()),
+                "file" => Property::File(value.into()),
+                "object" => Property::Object(self.property_value(&name, value)?),
+                "class" => Property::Class(self.properties(property)?),
+                _ => Property::String(value.to_owned()),
+            };
+
+            properties.insert(name, value);
+        }
+
+        Ok(properties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use base64::Engine;
+    use flate2::{
+        write::{
+            GzEncoder,
+            ZlibEncoder,
+        },
+        Compression,
+    };
+
+    use super::*;
+
+    const GIDS: [u32; 6] = [0, 1, 2, 0x8000_0003, 0x4000_0001, 7];
+
+    fn map(layers: &str) -> Map {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <map version="1.10" orientation="orthogonal" width="3" height="2" tilewidth="16" tileheight="8" infinite="0">
+                <properties>
+                    <property name="music" value="cave.ogg"/>
+                    <property name="gravity" type="float" value="9.5"/>
+                </properties>
+                <tileset firstgid="1" source="tiles.tsx"/>
+                <tileset firstgid="5">
+                    <image source="../images/terrain.png" width="64" height="64"/>
+                </tileset>
+                {}
+            </map>"#,
+            layers
+        );
+        parse_map(Path::new("test.tmx"), &xml).unwrap()
+    }
+
+    fn single_layer(data: &str) -> TileLayer {
+        let mut map = map(&format!(r#"<layer id="1" name="ground">{}</layer>"#, data));
+        match map.layers.remove(0) {
+            Layer::TileLayer(layer) => layer,
+            layer => panic!("expected a tile layer, got {:?}", layer),
+        }
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn gid_bytes() -> Vec<u8> {
+        GIDS.iter().flat_map(|gid| gid.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn map_attributes() {
+        let map = map("");
+
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!((map.tile_width, map.tile_height), (16, 8));
+        assert!(!map.infinite);
+        assert_eq!(
+            map.properties["music"],
+            Property::String("cave.ogg".to_owned())
+        );
+        assert_eq!(map.properties["gravity"], Property::Float(9.5));
+
+        assert_eq!(map.tilesets.len(), 2);
+        assert_eq!(map.tilesets[0].first_gid, 1);
+        assert_eq!(
+            map.tilesets[0].source.as_deref(),
+            Some(Path::new("tiles.tsx"))
+        );
+        assert_eq!(map.tilesets[1].first_gid, 5);
+        assert_eq!(
+            map.tilesets[1].image.as_deref(),
+            Some(Path::new("../images/terrain.png"))
+        );
+    }
+
+    #[test]
+    fn csv_layer() {
+        let layer = single_layer(
+            r#"<data encoding="csv">
+                0,1,2,
+                2147483651,1073741825,7
+            </data>"#,
+        );
+
+        assert_eq!(layer.info.name, "ground");
+        assert_eq!(layer.gids().unwrap(), GIDS);
+    }
+
+    #[test]
+    fn base64_layer() {
+        let layer = single_layer(&format!(
+            r#"<data encoding="base64">
+                {}
+            </data>"#,
+            base64(&gid_bytes())
+        ));
+
+        assert_eq!(layer.gids().unwrap(), GIDS);
+    }
+
+    #[test]
+    fn zlib_layer() {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(&gid_bytes()).unwrap();
+        let layer = single_layer(&format!(
+            r#"<data encoding="base64" compression="zlib">{}</data>"#,
+            base64(&encoder.finish().unwrap())
+        ));
+
+        assert_eq!(layer.gids().unwrap(), GIDS);
+    }
+
+    #[test]
+    fn gzip_layer() {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&gid_bytes()).unwrap();
+        let layer = single_layer(&format!(
+            r#"<data encoding="base64" compression="gzip">{}</data>"#,
+            base64(&encoder.finish().unwrap())
+        ));
+
+        assert_eq!(layer.gids().unwrap(), GIDS);
+    }
+
+    #[test]
+    fn unsupported_compression() {
+        let layer = single_layer(&format!(
+            r#"<data encoding="base64" compression="zstd">{}</data>"#,
+            base64(&gid_bytes())
+        ));
+
+        assert!(matches!(
+            layer.gids(),
+            Err(Error::UnsupportedCompression(compression)) if compression == "zstd"
+        ));
+    }
+
+    #[test]
+    fn tile_element_layer() {
+        let layer = single_layer(
+            r#"<data>
+                <tile/><tile gid="1"/><tile gid="2"/>
+                <tile gid="2147483651"/><tile gid="1073741825"/><tile gid="7"/>
+            </data>"#,
+        );
+
+        assert_eq!(layer.gids().unwrap(), GIDS);
+    }
+
+    #[test]
+    fn invalid_csv() {
+        let xml = r#"<map width="1" height="1" tilewidth="1" tileheight="1">
+            <layer><data encoding="csv">1,x</data></layer>
+        </map>"#;
+
+        assert!(matches!(
+            parse_map(Path::new("test.tmx"), xml),
+            Err(Error::InvalidMap { reason, .. }) if reason == "invalid tile `x`"
+        ));
+    }
+
+    #[test]
+    fn groups_and_objects() {
+        let map = map(r#"
+            <group name="background" offsetx="4" offsety="-2">
+                <imagelayer name="sky"/>
+                <objectgroup name="spawns">
+                    <object id="3" name="player" type="spawn" x="8" y="16"><point/></object>
+                    <object id="4" gid="2147483654" x="0" y="0" width="16" height="16"/>
+                    <object id="5" x="1" y="2">
+                        <polygon points="0,0 16,0 16,-8.5"/>
+                        <properties>
+                            <property name="target" type="object" value="3"/>
+                        </properties>
+                    </object>
+                </objectgroup>
+            </group>"#);
+
+        let group = match &map.layers[..] {
+            [Layer::Group(group)] => group,
+            layers => panic!("expected a single group, got {:?}", layers),
+        };
+        assert_eq!(group.info.name, "background");
+        assert_eq!((group.info.offset_x, group.info.offset_y), (4.0, -2.0));
+
+        let objects = match &group.layers[..] {
+            [Layer::ImageLayer(_), Layer::ObjectGroup(objects)] => &objects.objects,
+            layers => panic!("unexpected layers {:?}", layers),
+        };
+        assert_eq!(objects.len(), 3);
+        assert_eq!(objects[0].name, "player");
+        assert_eq!(objects[0].class, "spawn");
+        assert!(objects[0].point);
+        assert_eq!(objects[1].gid, Some(0x8000_0006));
+        let polygon = objects[2].polygon.as_ref().unwrap();
+        assert_eq!(polygon.len(), 3);
+        assert_eq!((polygon[2].x, polygon[2].y), (16.0, -8.5));
+        assert_eq!(objects[2].properties["target"], Property::Object(3));
+    }
+
+    #[test]
+    fn tileset() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <tileset version="1.10" name="props" tilewidth="16" tileheight="16" tilecount="2" columns="0">
+                <tile id="0"><image source="props/crate.png" width="16" height="16"/></tile>
+                <tile id="3"><image source="props/barrel.png" width="16" height="16"/></tile>
+            </tileset>"#;
+        let tileset = parse_tileset(Path::new("props.tsx"), xml).unwrap();
+
+        assert_eq!(tileset.first_gid, 0);
+        assert!(tileset.image.is_none());
+        assert_eq!(tileset.tiles.len(), 2);
+        assert_eq!(tileset.tiles[1].id, 3);
+        assert_eq!(
+            tileset.tiles[1].image.as_deref(),
+            Some(Path::new("props/barrel.png"))
+        );
+    }
+}
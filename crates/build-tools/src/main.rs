@@ -1,17 +1,37 @@
 mod args;
+mod cache;
+mod credits;
+mod font;
+mod level;
+mod manifest;
+mod palette;
+mod paths;
 mod sprite_sheet;
+mod validate;
+mod watch;
 
 use color_eyre::eyre::Error;
 use structopt::StructOpt;
 
 use crate::args::Args;
 
-#[tokio::main]
-async fn main() -> Result<(), Error> {
+fn main() -> Result<(), Error> {
     color_eyre::install()?;
 
+    // the .env file is optional, it's only used to set `RUST_LOG`. this sets
+    // environment variables, so it has to happen before the runtime starts its
+    // threads.
+    dotenv::dotenv().ok();
+
+    // the build tools are mostly run by hand, so we want to see what they're
+    // doing by default.
+    let filters = std::env::var("RUST_LOG").unwrap_or_else(|_| "build_tools=info".to_owned());
+    pretty_env_logger::formatted_builder()
+        .parse_filters(&filters)
+        .init();
+
     let args = Args::from_args();
-    args.run().await?;
+    tokio::runtime::Runtime::new()?.block_on(args.run())?;
 
     Ok(())
 }
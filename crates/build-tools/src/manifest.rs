@@ -0,0 +1,261 @@
+//! declarative asset manifest.
+//!
+//! instead of passing every file on the command line, a manifest lists the
+//! atlases that should be built and which files go into them. a manifest looks
+//! like this:
+//!
+//! ```toml
+//! [atlas.particles]
+//! output_texture = "atlases/particles.png"
+//! output_sprite_sheet = "atlases/particles.json"
+//!
+//! [[atlas.particles.group]]
+//! files = ["sprites/particles/*.png"]
+//! padding = "extrude"
+//! trim = true
+//! strip_prefix = "sprites"
+//! ```
+//!
+//! all paths are relative to the directory that contains the manifest. the
+//! manifest can be either toml or json, depending on its file extension.
+
+use std::{
+    collections::BTreeMap,
+    num::NonZeroU32,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::sprite_sheet::NineSlice;
+use euclid::default::Rect;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::sprite_sheet::{
+    AnimationDefinition,
+    FontOptions,
+    Grid,
+    HeightSource,
+    Input,
+    InputOptions,
+    Padding,
+    Slicing,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Pattern(#[from] glob::PatternError),
+
+    #[error("{0}")]
+    Glob(#[from] glob::GlobError),
+
+    #[error("`{path}` doesn't start with `{prefix}`")]
+    StripPrefix { path: PathBuf, prefix: PathBuf },
+
+    #[error("group uses both `grid` and `rects`")]
+    ConflictingSlicing,
+}
+
+/// a group of files in an atlas that share the same options.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Group {
+    /// glob patterns for the files in this group.
+    pub files: Vec<String>,
+
+    #[serde(default)]
+    pub padding: Padding,
+
+    /// remove transparent borders from the sprites.
+    #[serde(default)]
+    pub trim: bool,
+
+    /// allow rotating the sprites by 90° in the atlas, if that packs tighter.
+    #[serde(default)]
+    pub rotate: bool,
+
+    /// nine-slice insets for all sprites in this group, e.g.
+    /// `{ left = 4, top = 4, right = 4, bottom = 4 }`.
+    pub nine_slice: Option<NineSlice>,
+
+    /// convert the sprites into signed distance fields with this spread in
+    /// pixels, e.g. for icons that are drawn at different scales.
+    pub sdf: Option<NonZeroU32>,
+
+    /// turn numbered sprites like `particles/flame_01.png`,
+    /// `particles/flame_02.png` into an animation clip named `flame`, showing
+    /// each frame this many milliseconds.
+    pub frame_duration: Option<NonZeroU32>,
+
+    /// slice every file into tiles.
+    pub grid: Option<Grid>,
+
+    /// slice every file into these named rects.
+    pub rects: Option<BTreeMap<String, Rect<u32>>>,
+
+    /// prefix that is removed from the paths to get the sprite names. if not
+    /// set, sprites are named after their path relative to the manifest.
+    pub strip_prefix: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Atlas {
+    pub output_texture: PathBuf,
+
+    pub output_sprite_sheet: PathBuf,
+
+    /// where to write a rust module with typed ids for all sprites.
+    pub output_sprite_ids: Option<PathBuf>,
+
+    #[serde(default = "Atlas::default_max_page_size")]
+    pub max_page_size: u32,
+
+    /// premultiply the color channels of the atlas pages with alpha.
+    #[serde(default)]
+    pub premultiply: bool,
+
+    /// generate the mip levels of the atlas pages. neighbouring sprites only
+    /// stay apart for as many levels as their padding allows.
+    #[serde(default)]
+    pub mipmaps: bool,
+
+    /// generate normal maps for the atlas pages, with the heights guessed from
+    /// the `luminance` or the `alpha` of the sprites.
+    pub normal_maps: Option<HeightSource>,
+
+    #[serde(rename = "group")]
+    pub groups: Vec<Group>,
+
+    /// animation clips with explicit frames, by name, e.g.
+    /// `[atlas.sprites.animation.idle]` with `frames = ["idle_a.png",
+    /// "idle_b.png"]`, `duration = 200` and `direction = "PingPong"`.
+    #[serde(default, rename = "animation")]
+    pub animations: BTreeMap<String, AnimationDefinition>,
+}
+
+impl Atlas {
+    fn default_max_page_size() -> u32 {
+        crate::sprite_sheet::AtlasBuilder::DEFAULT_MAX_PAGE_SIZE as u32
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// the atlases by name.
+    #[serde(default, rename = "atlas")]
+    pub atlases: BTreeMap<String, Atlas>,
+
+    /// where to write the credits for all packed files. if this is set, every
+    /// packed file needs an attribution in a `credits.toml`, see
+    /// [`crate::credits`].
+    pub output_credits: Option<PathBuf>,
+
+    /// the directory that contains the manifest. all paths are relative to
+    /// this.
+    #[serde(skip)]
+    pub base_path: PathBuf,
+}
+
+impl Manifest {
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let s = tokio::fs::read_to_string(path).await?;
+
+        let mut manifest: Self = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&s)?
+        }
+        else {
+            toml::from_str(&s)?
+        };
+
+        manifest.base_path = path.parent().unwrap_or(Path::new("")).to_owned();
+
+        Ok(manifest)
+    }
+
+    /// resolves a path in the manifest.
+    pub fn path(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.base_path.join(path)
+    }
+
+    /// directories that contain the inputs of an atlas, i.e. that need to be
+    /// watched for changes.
+    pub fn input_dirs(&self, atlas: &Atlas) -> Vec<PathBuf> {
+        atlas
+            .groups
+            .iter()
+            .flat_map(|group| &group.files)
+            .map(|pattern| crate::watch::pattern_dir(&self.path(pattern)))
+            .collect()
+    }
+
+    /// expands the globs of all groups of an atlas.
+    pub fn inputs(&self, atlas: &Atlas) -> Result<Vec<Input>, Error> {
+        let mut inputs = vec![];
+
+        for group in &atlas.groups {
+            let slicing = match (&group.grid, &group.rects) {
+                (None, None) => Slicing::None,
+                (Some(grid), None) => Slicing::Grid(*grid),
+                (None, Some(rects)) => Slicing::Rects(rects.clone()),
+                (Some(_), Some(_)) => return Err(Error::ConflictingSlicing),
+            };
+            let options = InputOptions {
+                padding: group.padding,
+                slicing,
+                trim: group.trim,
+                rotate: group.rotate,
+                nine_slice: group.nine_slice,
+                sdf: group.sdf,
+                font: FontOptions::default(),
+                frame_duration: group.frame_duration,
+            };
+            let prefix = self.path(group.strip_prefix.as_deref().unwrap_or(Path::new("")));
+
+            for pattern in &group.files {
+                let pattern = self.path(pattern);
+                let mut num_files = 0;
+
+                for path in glob::glob(&pattern.to_string_lossy())? {
+                    let path = path?;
+                    let name = path
+                        .strip_prefix(&prefix)
+                        .map_err(|_| {
+                            Error::StripPrefix {
+                                path: path.clone(),
+                                prefix: prefix.clone(),
+                            }
+                        })?
+                        .to_owned();
+
+                    inputs.push(Input {
+                        path,
+                        name,
+                        options: options.clone(),
+                    });
+                    num_files += 1;
+                }
+
+                if num_files == 0 {
+                    log::warn!("`{}` doesn't match any files", pattern.display());
+                }
+            }
+        }
+
+        Ok(inputs)
+    }
+}
@@ -0,0 +1,239 @@
+//! palette extraction for recolorable sprites.
+//!
+//! a lot of our pixel art comes in multiple variants that are just recolors of
+//! each other. instead of storing every variant, we store a single index
+//! texture and a palette with one row per variant. the game can then recolor
+//! the sprite by picking a different palette row.
+
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+
+use assets::palette::Palette;
+use image::{
+    DynamicImage,
+    GrayImage,
+    ImageOutputFormat,
+    Luma,
+    Rgba,
+    RgbaImage,
+};
+use thiserror::Error;
+
+use crate::paths;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to load image `{path}`: {source}")]
+    Load {
+        path: PathBuf,
+        #[source]
+        source: image::ImageError,
+    },
+
+    #[error("no variants given")]
+    NoVariants,
+
+    #[error("variant `{path}` is {width}x{height}, but the first variant is {expected_width}x{expected_height}")]
+    SizeMismatch {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+        expected_width: u32,
+        expected_height: u32,
+    },
+
+    #[error(
+        "the variants have {num_colors} distinct colors, but only {} are supported",
+        Palette::MAX_COLORS
+    )]
+    TooManyColors { num_colors: usize },
+}
+
+/// a variant of a sprite, given as `name=path` or just `path`. in the latter
+/// case the variant is named after the file stem.
+#[derive(Clone, Debug)]
+pub struct Variant {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl FromStr for Variant {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let variant = if let Some((name, path)) = s.split_once('=') {
+            Self {
+                name: name.to_owned(),
+                path: path.into(),
+            }
+        }
+        else {
+            let path = PathBuf::from(s);
+            Self {
+                name: path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                path,
+            }
+        };
+
+        Ok(variant)
+    }
+}
+
+/// splits the `variants` of a sprite into an index texture and a palette
+/// texture with one row per variant.
+///
+/// a palette index stands for the colors a pixel has in all the variants, so
+/// pixels only share an index if they have the same color in every variant.
+/// fully transparent pixels all get the same index.
+pub fn extract(variants: &[(PathBuf, RgbaImage)]) -> Result<(GrayImage, RgbaImage), Error> {
+    let (_, first) = variants.first().ok_or(Error::NoVariants)?;
+    let (width, height) = first.dimensions();
+
+    for (path, image) in variants {
+        if image.dimensions() != (width, height) {
+            return Err(Error::SizeMismatch {
+                path: path.clone(),
+                width: image.width(),
+                height: image.height(),
+                expected_width: width,
+                expected_height: height,
+            });
+        }
+    }
+
+    let mut colors = vec![];
+    let mut indices = HashMap::new();
+    let mut index_texture = GrayImage::new(width, height);
+
+    for (x, y, index_pixel) in index_texture.enumerate_pixels_mut() {
+        let key = variants
+            .iter()
+            .map(|(_, image)| {
+                let pixel = *image.get_pixel(x, y);
+                if pixel[3] == 0 {
+                    Rgba([0, 0, 0, 0])
+                }
+                else {
+                    pixel
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let index = *indices.entry(key.clone()).or_insert_with(|| {
+            colors.push(key);
+            colors.len() - 1
+        });
+
+        // we keep counting to report how many colors there are.
+        if index < Palette::MAX_COLORS {
+            *index_pixel = Luma([index as u8]);
+        }
+    }
+
+    if colors.len() > Palette::MAX_COLORS {
+        return Err(Error::TooManyColors {
+            num_colors: colors.len(),
+        });
+    }
+
+    let mut palette_texture = RgbaImage::new(colors.len() as u32, variants.len() as u32);
+    for (index, color) in colors.iter().enumerate() {
+        for (row, pixel) in color.iter().enumerate() {
+            palette_texture.put_pixel(index as u32, row as u32, *pixel);
+        }
+    }
+
+    Ok((index_texture, palette_texture))
+}
+
+/// loads the variants of a sprite, extracts their palette and writes the index
+/// texture, palette texture and palette descriptor.
+pub async fn build(
+    output_index_texture: impl AsRef<Path>,
+    output_palette_texture: impl AsRef<Path>,
+    output_palette: impl AsRef<Path>,
+    variants: &[Variant],
+) -> Result<(), Error> {
+    let output_palette = output_palette.as_ref();
+
+    let mut images = Vec::with_capacity(variants.len());
+    for variant in variants {
+        log::debug!("loading `{}`", variant.path.display());
+
+        let data = tokio::fs::read(&variant.path).await?;
+        let image = image::load_from_memory(&data).map_err(|source| {
+            Error::Load {
+                path: variant.path.clone(),
+                source,
+            }
+        })?;
+        images.push((variant.path.clone(), image.into_rgba8()));
+    }
+
+    let (index_texture, palette_texture) = extract(&images)?;
+    log::info!(
+        "extracted {} colors in {} variants",
+        palette_texture.width(),
+        palette_texture.height()
+    );
+
+    let num_colors = palette_texture.width() as usize;
+    write_png(
+        output_index_texture.as_ref(),
+        DynamicImage::ImageLuma8(index_texture),
+    )
+    .await?;
+    write_png(
+        output_palette_texture.as_ref(),
+        DynamicImage::ImageRgba8(palette_texture),
+    )
+    .await?;
+
+    // texture paths in the descriptor are relative to the descriptor.
+    let relative = |path: &Path| paths::relative_to_file(path, output_palette);
+
+    let palette = Palette {
+        index_texture: relative(output_index_texture.as_ref()),
+        palette_texture: relative(output_palette_texture.as_ref()),
+        rows: variants
+            .iter()
+            .map(|variant| variant.name.clone())
+            .collect(),
+        num_colors,
+    };
+
+    let json = serde_json::to_vec_pretty(&palette)?;
+    tokio::fs::write(output_palette, json).await?;
+
+    Ok(())
+}
+
+async fn write_png(path: &Path, image: DynamicImage) -> Result<(), Error> {
+    log::debug!("writing `{}`", path.display());
+
+    let mut png = Cursor::new(vec![]);
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+    tokio::fs::write(path, png.into_inner()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! paths between the files that the build tools write.
+//!
+//! outputs refer to each other with relative paths, e.g. a sprite sheet to its
+//! pages, so the assets can be moved around together.
+
+use std::path::{
+    Component,
+    Path,
+    PathBuf,
+};
+
+/// makes `path` absolute, and removes `.` and `..` components. this only
+/// looks at the path, so it doesn't follow symlinks.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    }
+    else {
+        path
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in std::path::absolute(path).ok()?.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    Some(normalized)
+}
+
+/// `path` relative to the directory that contains `file`, e.g.
+/// `../atlases/platformer.json` for `atlases/platformer.json` relative to
+/// `levels/1.json`. relative paths are resolved against the current directory
+/// first.
+///
+/// if there is no relative path, e.g. because the paths are on different
+/// drives, the absolute `path` is returned.
+pub fn relative_to_file(path: &Path, file: &Path) -> PathBuf {
+    let dir = file.parent().unwrap_or_else(|| Path::new(""));
+    let (Some(path), Some(dir)) = (normalize(path), normalize(dir))
+    else {
+        return path.to_owned();
+    };
+
+    let num_common = path
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if num_common == 0 {
+        return path;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in dir.components().skip(num_common) {
+        relative.push("..");
+    }
+    relative.extend(path.components().skip(num_common));
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relative(path: &str, file: &str) -> PathBuf {
+        relative_to_file(Path::new(path), Path::new(file))
+    }
+
+    #[test]
+    fn same_directory() {
+        assert_eq!(
+            relative("atlases/a.png", "atlases/a.json"),
+            Path::new("a.png")
+        );
+        assert_eq!(relative("a.png", "a.json"), Path::new("a.png"));
+    }
+
+    #[test]
+    fn subdirectory() {
+        assert_eq!(
+            relative("atlases/pages/a.png", "atlases/a.json"),
+            Path::new("pages/a.png")
+        );
+    }
+
+    #[test]
+    fn sibling_directory() {
+        assert_eq!(
+            relative("atlases/platformer.json", "levels/1.json"),
+            Path::new("../atlases/platformer.json")
+        );
+        assert_eq!(
+            relative("out/a.png", "meta/a.json"),
+            Path::new("../out/a.png")
+        );
+        assert_eq!(
+            relative("a.png", "meta/deep/a.json"),
+            Path::new("../../a.png")
+        );
+    }
+
+    #[test]
+    fn parent_and_current_components() {
+        assert_eq!(
+            relative("./levels/../atlases/a.png", "atlases/./a.json"),
+            Path::new("a.png")
+        );
+        assert_eq!(relative("../a.png", "a.json"), Path::new("../a.png"));
+    }
+
+    #[test]
+    fn absolute_and_relative() {
+        let cwd = std::env::current_dir().unwrap();
+        let path = cwd.join("atlases/a.png");
+
+        assert_eq!(
+            relative_to_file(&path, Path::new("atlases/a.json")),
+            Path::new("a.png")
+        );
+        assert_eq!(
+            relative_to_file(Path::new("atlases/a.png"), &cwd.join("levels/1.json")),
+            Path::new("../atlases/a.png")
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! animation clips from numbered sprites.
+//!
+//! sprites like `particles/flame_01.png`, `particles/flame_02.png`, ... are a
+//! sequence, which becomes a clip named `flame` with the frames in the order of
+//! their numbers. clips can also be defined explicitly, e.g. if the frames
+//! aren't numbered, or need a different direction.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    num::NonZeroU32,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::sprite_sheet::{
+    Animation,
+    Direction,
+    Frame,
+    Sprite,
+};
+use serde::Deserialize;
+
+use crate::sprite_sheet::Error;
+
+/// an animation clip, that lists its frames explicitly.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationDefinition {
+    /// names of the sprites in the sprite sheet.
+    pub frames: Vec<PathBuf>,
+
+    /// how long each frame is shown, in milliseconds.
+    #[serde(default = "AnimationDefinition::default_duration")]
+    pub duration: NonZeroU32,
+
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+impl AnimationDefinition {
+    pub const DEFAULT_DURATION: u32 = 100;
+
+    fn default_duration() -> NonZeroU32 {
+        NonZeroU32::new(Self::DEFAULT_DURATION).unwrap()
+    }
+
+    /// the clip, if all frames exist in `sprites`.
+    pub fn animation(
+        &self,
+        name: &str,
+        sprites: &HashMap<PathBuf, Sprite>,
+    ) -> Result<Animation, Error> {
+        let frames = self
+            .frames
+            .iter()
+            .map(|sprite| {
+                if sprites.contains_key(sprite) {
+                    Ok(Frame {
+                        sprite: sprite.clone(),
+                        duration: self.duration.get(),
+                    })
+                }
+                else {
+                    Err(Error::UnknownFrame {
+                        animation: name.to_owned(),
+                        sprite: sprite.clone(),
+                    })
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Animation {
+            frames,
+            direction: self.direction,
+        })
+    }
+}
+
+/// splits a sprite name like `particles/flame_01.png` into the directory, the
+/// name of the sequence and the frame number, i.e. `particles`, `flame` and
+/// `1`. sprites sliced from a file (with a `#` in their name) are never part
+/// of a sequence.
+fn sequence_frame(name: &Path) -> Option<(&Path, &str, u32)> {
+    let stem = name.file_stem()?.to_str()?;
+    if name.to_str()?.contains('#') {
+        return None;
+    }
+
+    let (sequence, number) = stem.rsplit_once('_')?;
+    if sequence.is_empty() || number.is_empty() || !number.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((
+        name.parent().unwrap_or_else(|| Path::new("")),
+        sequence,
+        number.parse().ok()?,
+    ))
+}
+
+/// finds numbered sequences in `sprites`, which are pairs of a sprite name and
+/// how long it's shown as a frame. sequences need at least two frames, and are
+/// named after the file name without the number.
+pub fn detect_sequences(
+    sprites: &[(PathBuf, NonZeroU32)],
+) -> Result<HashMap<String, Animation>, Error> {
+    let mut sequences = BTreeMap::<_, Vec<_>>::new();
+    for (name, duration) in sprites {
+        if let Some((dir, sequence, number)) = sequence_frame(name) {
+            sequences
+                .entry((dir, sequence))
+                .or_default()
+                .push((number, name, *duration));
+        }
+    }
+
+    let mut animations = HashMap::new();
+
+    for ((_, sequence), mut frames) in sequences {
+        if frames.len() < 2 {
+            continue;
+        }
+
+        // sequences with the same name in different directories.
+        if animations.contains_key(sequence) {
+            return Err(Error::DuplicateAnimation {
+                name: sequence.to_owned(),
+            });
+        }
+
+        frames.sort();
+        animations.insert(
+            sequence.to_owned(),
+            Animation {
+                frames: frames
+                    .into_iter()
+                    .map(|(_, name, duration)| {
+                        Frame {
+                            sprite: name.clone(),
+                            duration: duration.get(),
+                        }
+                    })
+                    .collect(),
+                direction: Direction::Forward,
+            },
+        );
+    }
+
+    Ok(animations)
+}
@@ -0,0 +1,118 @@
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use asefile::{
+    AnimationDirection,
+    AsepriteFile,
+};
+use assets::sprite_sheet::{
+    Animation,
+    Direction,
+    Frame,
+};
+use image::RgbaImage;
+
+use crate::sprite_sheet::Error;
+
+/// the frames and animation clips of an aseprite file.
+pub struct Aseprite {
+    /// the flattened frames and their sprite paths.
+    pub frames: Vec<(PathBuf, RgbaImage)>,
+
+    /// animation clips for the tags in the file.
+    pub animations: HashMap<String, Animation>,
+}
+
+pub fn is_aseprite(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("aseprite" | "ase")
+    )
+}
+
+/// sprite path for a frame of an aseprite file. if the file only has a single
+/// frame, this is just the path of the file, so it can be used in place of an
+/// exported png. otherwise the frame index is appended, e.g.
+/// `exploding_head_pixelart.aseprite#2`.
+fn frame_path(path: &Path, frame: u32, num_frames: u32) -> PathBuf {
+    if num_frames == 1 {
+        path.to_owned()
+    }
+    else {
+        let mut frame_path = path.as_os_str().to_owned();
+        frame_path.push(format!("#{}", frame));
+        frame_path.into()
+    }
+}
+
+/// loads an aseprite file from `data`. `path` is only used for error
+/// messages, the sprites and clips are named after `name`.
+///
+/// every frame becomes its own sprite, with all visible layers flattened. every
+/// tag becomes an animation clip named `<file stem>/<tag name>`. if the file
+/// has multiple frames but no tags, all frames become a clip named after the
+/// file stem.
+pub fn load(path: &Path, name: &Path, data: &[u8]) -> Result<Aseprite, Error> {
+    let file = AsepriteFile::read(Cursor::new(data)).map_err(|source| {
+        Error::Aseprite {
+            path: path.to_owned(),
+            source,
+        }
+    })?;
+
+    let num_frames = file.num_frames();
+    let frames = (0..num_frames)
+        .map(|frame| {
+            (
+                frame_path(name, frame, num_frames),
+                file.frame(frame).image(),
+            )
+        })
+        .collect();
+
+    let clip = |from_frame: u32, to_frame: u32, direction: Direction| {
+        Animation {
+            frames: (from_frame..=to_frame)
+                .map(|frame| {
+                    Frame {
+                        sprite: frame_path(name, frame, num_frames),
+                        duration: file.frame(frame).duration(),
+                    }
+                })
+                .collect(),
+            direction,
+        }
+    };
+
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let mut animations = HashMap::new();
+
+    for tag in (0..file.num_tags()).map(|tag| file.tag(tag)) {
+        let direction = match tag.animation_direction() {
+            AnimationDirection::Forward => Direction::Forward,
+            AnimationDirection::Reverse => Direction::Reverse,
+            AnimationDirection::PingPong => Direction::PingPong,
+        };
+
+        let animation = format!("{}/{}", stem, tag.name());
+        if animations.contains_key(&animation) {
+            return Err(Error::DuplicateAnimation { name: animation });
+        }
+        animations.insert(animation, clip(tag.from_frame(), tag.to_frame(), direction));
+    }
+
+    if file.num_tags() == 0 && num_frames > 1 {
+        animations.insert(
+            stem.into_owned(),
+            clip(0, num_frames - 1, Direction::Forward),
+        );
+    }
+
+    Ok(Aseprite { frames, animations })
+}
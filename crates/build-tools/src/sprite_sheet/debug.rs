@@ -0,0 +1,274 @@
+//! debug output for atlases.
+//!
+//! the overlay images show where every sprite ended up in the atlas pages. the
+//! sprites are outlined and labelled with a number, which the text report maps
+//! to the sprite paths. the report also has statistics to tune the packing.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fmt::Write,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::sprite_sheet::Sprite;
+use euclid::default::Rect;
+use image::{
+    Rgba,
+    RgbaImage,
+};
+
+/// 3x5 pixel glyphs for the digits. each row is 3 bits, with the most
+/// significant bit on the left.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// outline colors. neighbouring sprites get different colors.
+const COLORS: [Rgba<u8>; 6] = [
+    Rgba([255, 0, 0, 255]),
+    Rgba([0, 255, 0, 255]),
+    Rgba([0, 128, 255, 255]),
+    Rgba([255, 255, 0, 255]),
+    Rgba([255, 0, 255, 255]),
+    Rgba([0, 255, 255, 255]),
+];
+
+/// how many of the largest sprites are listed in the report.
+const NUM_LARGEST: usize = 10;
+
+/// a rect in the atlas, and the paths of all sprites that use it.
+struct Entry<'a> {
+    page: usize,
+    rect: Rect<u32>,
+    paths: Vec<&'a Path>,
+}
+
+/// the debug output for an atlas.
+pub struct DebugOutput<'a> {
+    /// unique rects in the order they're labelled, i.e. by page and position.
+    entries: Vec<Entry<'a>>,
+}
+
+impl<'a> DebugOutput<'a> {
+    pub fn new(sprites: &'a HashMap<PathBuf, Sprite>) -> Self {
+        let mut entries = BTreeMap::new();
+
+        for (path, sprite) in sprites {
+            let key = (
+                sprite.page,
+                sprite.rect.origin.y,
+                sprite.rect.origin.x,
+                sprite.rect.size.width,
+                sprite.rect.size.height,
+            );
+            entries
+                .entry(key)
+                .or_insert_with(|| {
+                    Entry {
+                        page: sprite.page,
+                        rect: sprite.rect,
+                        paths: vec![],
+                    }
+                })
+                .paths
+                .push(path.as_path());
+        }
+
+        let mut entries = entries.into_values().collect::<Vec<_>>();
+        for entry in &mut entries {
+            entry.paths.sort();
+        }
+
+        Self { entries }
+    }
+
+    /// draws the outlines and labels of all sprites on `page` onto a copy of
+    /// the page texture.
+    pub fn overlay(&self, page: usize, texture: &RgbaImage) -> RgbaImage {
+        let mut overlay = texture.clone();
+
+        for (label, entry) in self.entries.iter().enumerate() {
+            if entry.page != page {
+                continue;
+            }
+
+            let color = COLORS[label % COLORS.len()];
+            let rect = entry.rect;
+            for x in rect.min_x()..rect.max_x() {
+                put_pixel(&mut overlay, x, rect.min_y(), color);
+                put_pixel(&mut overlay, x, rect.max_y() - 1, color);
+            }
+            for y in rect.min_y()..rect.max_y() {
+                put_pixel(&mut overlay, rect.min_x(), y, color);
+                put_pixel(&mut overlay, rect.max_x() - 1, y, color);
+            }
+
+            draw_label(&mut overlay, rect.min_x() + 1, rect.min_y() + 1, label);
+        }
+
+        overlay
+    }
+
+    /// writes the text report. `page_sizes` are the sizes of the atlas pages
+    /// and `duplicates` are the deduplicated sprites, as returned by
+    /// [`super::AtlasBuilder::duplicates`].
+    pub fn report(
+        &self,
+        output_texture: &Path,
+        page_sizes: &[(u32, u32)],
+        duplicates: &[(PathBuf, Vec<PathBuf>)],
+    ) -> String {
+        // writing to a string can't fail.
+        let mut report = String::new();
+        writeln!(report, "atlas `{}`", output_texture.display()).unwrap();
+
+        writeln!(report).unwrap();
+        let mut total_area = 0;
+        let mut total_used = 0;
+        for (page, (width, height)) in page_sizes.iter().enumerate() {
+            let area = u64::from(*width) * u64::from(*height);
+            let (num_sprites, used) = self
+                .entries
+                .iter()
+                .filter(|entry| entry.page == page)
+                .fold((0, 0), |(n, used), entry| {
+                    (n + 1, used + area_of(&entry.rect))
+                });
+            total_area += area;
+            total_used += used;
+
+            writeln!(
+                report,
+                "page {}: {}x{}, {} sprites, {:.1}% filled, {} pixels wasted",
+                page,
+                width,
+                height,
+                num_sprites,
+                percent(used, area),
+                area - used
+            )
+            .unwrap();
+        }
+        writeln!(
+            report,
+            "total: {} pages, {:.1}% filled, {} pixels wasted",
+            page_sizes.len(),
+            percent(total_used, total_area),
+            total_area - total_used
+        )
+        .unwrap();
+
+        writeln!(report).unwrap();
+        writeln!(report, "largest sprites:").unwrap();
+        let mut largest = self.entries.iter().enumerate().collect::<Vec<_>>();
+        largest.sort_by_key(|(_, entry)| std::cmp::Reverse(area_of(&entry.rect)));
+        for (label, entry) in largest.into_iter().take(NUM_LARGEST) {
+            writeln!(
+                report,
+                " - #{} `{}`: {}x{} ({} pixels)",
+                label,
+                entry.paths[0].display(),
+                entry.rect.size.width,
+                entry.rect.size.height,
+                area_of(&entry.rect)
+            )
+            .unwrap();
+        }
+
+        if !duplicates.is_empty() {
+            writeln!(report).unwrap();
+            writeln!(report, "duplicates:").unwrap();
+            for (original, duplicates) in duplicates {
+                writeln!(report, " - `{}`:", original.display()).unwrap();
+                for duplicate in duplicates {
+                    writeln!(report, "   - `{}`", duplicate.display()).unwrap();
+                }
+            }
+        }
+
+        writeln!(report).unwrap();
+        writeln!(report, "sprites:").unwrap();
+        for (label, entry) in self.entries.iter().enumerate() {
+            writeln!(
+                report,
+                " - #{}: page {}, {}x{} at ({}, {})",
+                label,
+                entry.page,
+                entry.rect.size.width,
+                entry.rect.size.height,
+                entry.rect.origin.x,
+                entry.rect.origin.y
+            )
+            .unwrap();
+            for path in &entry.paths {
+                writeln!(report, "   - `{}`", path.display()).unwrap();
+            }
+        }
+
+        report
+    }
+}
+
+fn area_of(rect: &Rect<u32>) -> u64 {
+    u64::from(rect.size.width) * u64::from(rect.size.height)
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    }
+    else {
+        100.0 * part as f64 / total as f64
+    }
+}
+
+/// like [`RgbaImage::put_pixel`], but ignores pixels outside of the image.
+fn put_pixel(image: &mut RgbaImage, x: u32, y: u32, color: Rgba<u8>) {
+    if x < image.width() && y < image.height() {
+        image.put_pixel(x, y, color);
+    }
+}
+
+/// draws a number in white on a black box, with its top-left corner at `x`,
+/// `y`.
+fn draw_label(image: &mut RgbaImage, x: u32, y: u32, label: usize) {
+    let digits = label.to_string();
+    let width = 4 * digits.len() as u32 + 1;
+
+    for dy in 0..7 {
+        for dx in 0..width {
+            put_pixel(image, x + dx, y + dy, Rgba([0, 0, 0, 255]));
+        }
+    }
+
+    for (i, digit) in digits.bytes().enumerate() {
+        let glyph = &DIGITS[(digit - b'0') as usize];
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) != 0 {
+                    put_pixel(
+                        image,
+                        x + 1 + 4 * i as u32 + column,
+                        y + 1 + row as u32,
+                        Rgba([255, 255, 255, 255]),
+                    );
+                }
+            }
+        }
+    }
+}
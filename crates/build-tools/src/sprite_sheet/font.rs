@@ -0,0 +1,201 @@
+//! bitmap fonts from truetype and opentype fonts.
+//!
+//! fonts are rasterized at fixed pixel sizes, so the game can draw text with
+//! the same sprite pipeline as everything else. every glyph becomes a sprite
+//! named `<path>#<size>/<codepoint>`, e.g. `fonts/dejavu.ttf#16/U+0041`.
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use ab_glyph::{
+    point,
+    Font as _,
+    FontRef,
+    GlyphId,
+    OutlinedGlyph,
+    ScaleFont,
+};
+use assets::font::{
+    FontSize,
+    Glyph,
+    Kerning,
+};
+use euclid::default::Vector2D;
+use image::{
+    Rgba,
+    RgbaImage,
+};
+
+use crate::sprite_sheet::Error;
+
+/// which glyphs of a font are rasterized, and at which sizes.
+#[derive(Clone, Debug)]
+pub struct FontOptions {
+    /// font sizes in pixels.
+    pub sizes: Vec<u32>,
+
+    /// the characters to rasterize.
+    pub chars: String,
+}
+
+impl FontOptions {
+    pub const DEFAULT_SIZE: u32 = 16;
+
+    /// all printable ascii characters.
+    pub fn default_chars() -> String {
+        (' '..='~').collect()
+    }
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            sizes: vec![Self::DEFAULT_SIZE],
+            chars: Self::default_chars(),
+        }
+    }
+}
+
+/// the rasterized glyphs and metrics of a font.
+pub struct BitmapFont {
+    /// the glyph images and their sprite paths.
+    pub glyphs: Vec<(PathBuf, RgbaImage)>,
+
+    /// the metrics for every size, sorted by size.
+    pub sizes: Vec<FontSize>,
+}
+
+pub fn is_font(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("ttf" | "otf")
+    )
+}
+
+/// sprite path for a glyph, e.g. `fonts/dejavu.ttf#16/U+0041`.
+fn glyph_path(name: &Path, size: u32, c: char) -> PathBuf {
+    let mut glyph_path = name.as_os_str().to_owned();
+    glyph_path.push(format!("#{}/U+{:04X}", size, c as u32));
+    glyph_path.into()
+}
+
+/// loads a font from `data` and rasterizes it at all sizes in `options`.
+/// `path` is only used for error messages, the sprites are named after
+/// `name`.
+///
+/// glyphs are rendered white, with their coverage in the alpha channel, so
+/// they can be tinted when drawn. characters that aren't in the font are
+/// skipped.
+pub fn load(
+    path: &Path,
+    name: &Path,
+    data: &[u8],
+    options: &FontOptions,
+) -> Result<BitmapFont, Error> {
+    let font = FontRef::try_from_slice(data).map_err(|source| {
+        Error::Font {
+            path: path.to_owned(),
+            source,
+        }
+    })?;
+
+    let mut chars = BTreeSet::new();
+    for c in options.chars.chars() {
+        if font.glyph_id(c) == GlyphId(0) {
+            log::warn!("`{}` has no glyph for {:?}", path.display(), c);
+        }
+        else {
+            chars.insert(c);
+        }
+    }
+
+    let mut sizes = options.sizes.clone();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let mut bitmap_font = BitmapFont {
+        glyphs: vec![],
+        sizes: Vec::with_capacity(sizes.len()),
+    };
+
+    for size in sizes {
+        let scaled = font.as_scaled(size as f32);
+        let mut glyphs = BTreeMap::new();
+
+        for &c in &chars {
+            let id = font.glyph_id(c);
+            let mut glyph = Glyph {
+                sprite: None,
+                offset: Vector2D::zero(),
+                advance: scaled.h_advance(id),
+            };
+
+            // the glyph is positioned with its origin on the baseline, so the
+            // pixel bounds are relative to the pen position.
+            let outlined = scaled
+                .outline_glyph(id.with_scale_and_position(scaled.scale(), point(0.0, 0.0)))
+                .filter(|outlined| {
+                    let bounds = outlined.px_bounds();
+                    bounds.width() > 0.0 && bounds.height() > 0.0
+                });
+
+            if let Some(outlined) = outlined {
+                let bounds = outlined.px_bounds();
+                let sprite = glyph_path(name, size, c);
+
+                glyph.sprite = Some(sprite.clone());
+                glyph.offset = Vector2D::new(bounds.min.x as i32, bounds.min.y as i32);
+                bitmap_font.glyphs.push((sprite, rasterize(&outlined)));
+            }
+
+            glyphs.insert(c, glyph);
+        }
+
+        // the pairs are visited in order, so the kerning is sorted.
+        let mut kerning = vec![];
+        for &left in &chars {
+            for &right in &chars {
+                let amount = scaled.kern(font.glyph_id(left), font.glyph_id(right));
+                if amount != 0.0 {
+                    kerning.push(Kerning {
+                        left,
+                        right,
+                        amount,
+                    });
+                }
+            }
+        }
+
+        bitmap_font.sizes.push(FontSize {
+            size,
+            ascent: scaled.ascent(),
+            descent: scaled.descent(),
+            line_gap: scaled.line_gap(),
+            glyphs,
+            kerning,
+        });
+    }
+
+    Ok(bitmap_font)
+}
+
+/// renders a glyph in white, with its coverage as alpha.
+fn rasterize(outlined: &OutlinedGlyph) -> RgbaImage {
+    let bounds = outlined.px_bounds();
+    let mut image = RgbaImage::new(bounds.width() as u32, bounds.height() as u32);
+
+    outlined.draw(|x, y, coverage| {
+        let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+        image.put_pixel(x, y, Rgba([255, 255, 255, alpha]));
+    });
+
+    image
+}
@@ -0,0 +1,134 @@
+//! typed sprite ids.
+//!
+//! generates a rust module with a [`assets::sprite_sheet::SpriteId`] constant
+//! for every sprite in a sprite sheet. the game includes the module, so a
+//! sprite that doesn't exist anymore is a compile error instead of the wrong
+//! sprite on screen.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use crate::sprite_sheet::Error;
+
+/// name of the constant for a sprite, e.g. `EMOTES_EMOTE_FACE_HAPPY` for
+/// `emotes/emote_faceHappy.png`, or `EXPLODING_HEAD_2` for
+/// `exploding_head.aseprite#2`.
+///
+/// the extension of the file is dropped, everything else is turned into
+/// screaming snake case.
+pub fn constant_name(name: &Path) -> String {
+    let name = name.to_string_lossy();
+    let (path, suffix) = match name.split_once('#') {
+        Some((path, suffix)) => (path, Some(suffix)),
+        None => (&*name, None),
+    };
+    let path = Path::new(path).with_extension("");
+    let path = path.to_string_lossy();
+
+    let mut constant = String::new();
+    let mut previous = None::<char>;
+    for c in path
+        .chars()
+        .chain(suffix.map(|_| '#'))
+        .chain(suffix.unwrap_or("").chars())
+    {
+        if c.is_ascii_alphanumeric() {
+            // split camel case words.
+            let camel_case = c.is_ascii_uppercase()
+                && previous.is_some_and(|previous| {
+                    previous.is_ascii_lowercase() || previous.is_ascii_digit()
+                });
+            if camel_case && !constant.is_empty() {
+                constant.push('_');
+            }
+            constant.push(c.to_ascii_uppercase());
+        }
+        else if !constant.is_empty() && !constant.ends_with('_') {
+            constant.push('_');
+        }
+        previous = Some(c);
+    }
+
+    let constant = constant.trim_end_matches('_');
+    if constant.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        constant.to_owned()
+    }
+    else {
+        format!("SPRITE_{}", constant)
+    }
+}
+
+/// name of the constant with all ids. a sprite can't have it as its id.
+pub const ALL: &str = "ALL";
+
+/// generates the module with the ids for all `sprites` of the sprite sheet at
+/// `sprite_sheet`. the constants are sorted by name, and there is an [`ALL`]
+/// constant with all ids.
+///
+/// the module doesn't have inner attributes or `use` declarations, so it can
+/// be pulled in with `include!`.
+pub fn generate<'a>(
+    sprite_sheet: &Path,
+    sprites: impl IntoIterator<Item = &'a PathBuf>,
+) -> Result<String, Error> {
+    let mut constants = BTreeMap::new();
+    for sprite in sprites {
+        let constant = constant_name(sprite);
+        if constant == ALL {
+            return Err(Error::ReservedSpriteId {
+                id: constant,
+                sprite: sprite.clone(),
+            });
+        }
+        if let Some(other) = constants.insert(constant.clone(), sprite) {
+            return Err(Error::DuplicateSpriteId {
+                id: constant,
+                first: other.clone(),
+                second: sprite.clone(),
+            });
+        }
+    }
+
+    // writing to a string can't fail.
+    let mut module = String::new();
+    writeln!(
+        module,
+        "// sprite ids for `{}`. generated by build-tools, don't edit.",
+        sprite_sheet.display()
+    )
+    .unwrap();
+
+    for (constant, sprite) in &constants {
+        let name = sprite.to_string_lossy();
+        writeln!(module).unwrap();
+        writeln!(module, "/// `{}`", name).unwrap();
+        writeln!(
+            module,
+            "pub const {}: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new({:?});",
+            constant, name
+        )
+        .unwrap();
+    }
+
+    writeln!(module).unwrap();
+    writeln!(module, "/// all sprites in the sprite sheet.").unwrap();
+    writeln!(
+        module,
+        "pub const {}: [assets::sprite_sheet::SpriteId; {}] = [",
+        ALL,
+        constants.len()
+    )
+    .unwrap();
+    for constant in constants.keys() {
+        writeln!(module, "    {},", constant).unwrap();
+    }
+    writeln!(module, "];").unwrap();
+
+    Ok(module)
+}
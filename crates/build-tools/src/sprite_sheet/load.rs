@@ -0,0 +1,64 @@
+//! loading built sprite sheets, e.g. to check them or to resolve sprites
+//! against them.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use assets::sprite_sheet::{
+    DecodeError,
+    SpriteSheet,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("failed to read sprite sheet `{path}`: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid sprite sheet `{path}`: {source}")]
+    Json {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("invalid binary sprite sheet `{path}`: {source}")]
+    Decode {
+        path: PathBuf,
+        #[source]
+        source: DecodeError,
+    },
+}
+
+/// loads a sprite sheet, either json or binary.
+pub async fn load(path: &Path) -> Result<SpriteSheet, LoadError> {
+    let data = tokio::fs::read(path).await.map_err(|source| {
+        LoadError::Io {
+            path: path.to_owned(),
+            source,
+        }
+    })?;
+
+    if SpriteSheet::is_binary(&data) {
+        SpriteSheet::from_binary(&data).map_err(|source| {
+            LoadError::Decode {
+                path: path.to_owned(),
+                source,
+            }
+        })
+    }
+    else {
+        serde_json::from_slice(&data).map_err(|source| {
+            LoadError::Json {
+                path: path.to_owned(),
+                source,
+            }
+        })
+    }
+}
@@ -0,0 +1,178 @@
+//! premultiplied alpha and mip chains for atlas pages.
+//!
+//! downsampling a whole atlas page would blend neighbouring sprites into each
+//! other. instead every texel of a mip level only averages texels of the
+//! sprite it belongs to, including the sprite's padding.
+//!
+//! this isn't a hard guarantee though. the regions of the sprites are rounded
+//! outwards at every level, so at lower levels neighbouring regions overlap,
+//! and the texels they share are overwritten by the region that is downsampled
+//! last. the more padding a sprite has, the more levels it stays clean for.
+//!
+//! the pages are sRGB encoded, so colors are converted to linear before they
+//! are multiplied or averaged, and encoded again afterwards. otherwise the mip
+//! levels and the edges of premultiplied sprites get too dark.
+
+use std::sync::OnceLock;
+
+use euclid::default::{
+    Box2D,
+    Rect,
+};
+use image::{
+    Rgba,
+    RgbaImage,
+};
+
+/// converts an sRGB encoded channel to linear, in `0.0..=1.0`.
+fn to_linear(channel: u8) -> f32 {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|channel| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.04045 {
+                channel / 12.92
+            }
+            else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    })[usize::from(channel)]
+}
+
+/// converts a linear channel in `0.0..=1.0` to sRGB.
+fn to_srgb(channel: f32) -> u8 {
+    let channel = channel.clamp(0.0, 1.0);
+    let channel = if channel <= 0.0031308 {
+        channel * 12.92
+    }
+    else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    };
+    (channel * 255.0).round() as u8
+}
+
+/// multiplies the color channels of every pixel with its alpha, in linear
+/// space.
+pub fn premultiply(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        let alpha = f32::from(pixel[3]) / 255.0;
+        for channel in &mut pixel.0[..3] {
+            *channel = to_srgb(to_linear(*channel) * alpha);
+        }
+    }
+}
+
+/// generates the mip levels of an atlas page, starting at level 1, down to a
+/// single texel. `regions` are the rects of all sprites on the page, including
+/// their padding.
+pub fn mip_chain(page: &RgbaImage, regions: &[Rect<u32>], premultiplied: bool) -> Vec<RgbaImage> {
+    let mut levels = vec![];
+    let mut regions = regions.iter().map(Rect::to_box2d).collect::<Vec<_>>();
+    let mut source = page;
+
+    while source.width() > 1 || source.height() > 1 {
+        let (level, next_regions) = downsample(source, &regions, premultiplied);
+        levels.push(level);
+        regions = next_regions;
+        source = levels.last().unwrap();
+    }
+
+    levels
+}
+
+/// halves the size of `source`. returns the downsampled image and the
+/// regions at the new size.
+fn downsample(
+    source: &RgbaImage,
+    regions: &[Box2D<u32>],
+    premultiplied: bool,
+) -> (RgbaImage, Vec<Box2D<u32>>) {
+    let width = (source.width() / 2).max(1);
+    let height = (source.height() / 2).max(1);
+    let mut target = RgbaImage::new(width, height);
+
+    // texels that don't belong to any sprite just average everything.
+    let everything = Box2D::new((0, 0).into(), (source.width(), source.height()).into());
+    for (x, y, pixel) in target.enumerate_pixels_mut() {
+        *pixel = average(source, x, y, &everything, premultiplied);
+    }
+
+    let mut target_regions = Vec::with_capacity(regions.len());
+    for region in regions {
+        // round outwards, so that the region covers all texels that contain
+        // some of the sprite. this makes neighbouring regions overlap, and
+        // the texels they share end up with the later region's average.
+        let target_region = Box2D::new(
+            (region.min.x / 2, region.min.y / 2).into(),
+            (
+                region.max.x.div_ceil(2).min(width),
+                region.max.y.div_ceil(2).min(height),
+            )
+                .into(),
+        );
+
+        for y in target_region.min.y..target_region.max.y {
+            for x in target_region.min.x..target_region.max.x {
+                target.put_pixel(x, y, average(source, x, y, region, premultiplied));
+            }
+        }
+
+        target_regions.push(target_region);
+    }
+
+    (target, target_regions)
+}
+
+/// averages the up to 2x2 texels in `source` that end up in texel `x`, `y` of
+/// the next mip level, but only those inside of `region`.
+///
+/// colors are averaged in linear space and weighted by their alpha, so that
+/// transparent texels don't darken the result.
+fn average(
+    source: &RgbaImage,
+    x: u32,
+    y: u32,
+    region: &Box2D<u32>,
+    premultiplied: bool,
+) -> Rgba<u8> {
+    let mut color = [0f32; 3];
+    let mut alpha = 0;
+    let mut count = 0;
+
+    // the size of the next level is rounded down, so if the source has an
+    // odd size, its last row or column is dropped. only a source that is a
+    // single texel wide or high has less than 2 texels to average.
+    let max_x = (2 * x + 2).min(source.width()).min(region.max.x);
+    let max_y = (2 * y + 2).min(source.height()).min(region.max.y);
+
+    for sy in (2 * y).max(region.min.y)..max_y {
+        for sx in (2 * x).max(region.min.x)..max_x {
+            let pixel = source.get_pixel(sx, sy);
+            let a = u32::from(pixel[3]);
+            for (sum, channel) in color.iter_mut().zip(&pixel.0[..3]) {
+                if premultiplied {
+                    *sum += to_linear(*channel);
+                }
+                else {
+                    *sum += to_linear(*channel) * a as f32;
+                }
+            }
+            alpha += a;
+            count += 1;
+        }
+    }
+
+    if count == 0 || alpha == 0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    let divisor = if premultiplied { count } else { alpha } as f32;
+    Rgba([
+        to_srgb(color[0] / divisor),
+        to_srgb(color[1] / divisor),
+        to_srgb(color[2] / divisor),
+        ((alpha + count / 2) / count) as u8,
+    ])
+}
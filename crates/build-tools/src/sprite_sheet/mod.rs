@@ -0,0 +1,1180 @@
+mod animation;
+mod aseprite;
+mod debug;
+pub mod font;
+mod ids;
+mod load;
+mod mipmap;
+mod normal_map;
+mod padding;
+mod sdf;
+mod slice;
+mod trim;
+
+use std::{
+    cmp::Reverse,
+    collections::{
+        BTreeMap,
+        HashMap,
+        HashSet,
+    },
+    io::Cursor,
+    num::NonZeroU32,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use assets::sprite_sheet::{
+    NineSlice,
+    Sdf,
+    Sprite,
+    SpriteSheet,
+    Trim,
+};
+use euclid::default::Rect;
+use guillotiere::{
+    Allocation,
+    AtlasAllocator,
+    Size,
+};
+use image::{
+    imageops,
+    ImageOutputFormat,
+    RgbaImage,
+};
+use thiserror::Error;
+
+use self::debug::DebugOutput;
+pub use self::{
+    animation::AnimationDefinition,
+    font::FontOptions,
+    load::{
+        load,
+        LoadError,
+    },
+    normal_map::HeightSource,
+    padding::Padding,
+    slice::{
+        Grid,
+        Slicing,
+    },
+};
+use crate::{
+    cache::Cache,
+    paths,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Encode(#[from] assets::sprite_sheet::EncodeError),
+
+    #[error("failed to load image `{path}`: {source}")]
+    Load {
+        path: PathBuf,
+        #[source]
+        source: image::ImageError,
+    },
+
+    #[error("failed to load aseprite file `{path}`: {source}")]
+    Aseprite {
+        path: PathBuf,
+        #[source]
+        source: asefile::AsepriteParseError,
+    },
+
+    #[error("failed to load font `{path}`: {source}")]
+    Font {
+        path: PathBuf,
+        #[source]
+        source: ab_glyph::InvalidFont,
+    },
+
+    #[error("slice `{name}` is outside of image `{path}`")]
+    SliceOutOfBounds { path: PathBuf, name: String },
+
+    #[error("nine-slice insets don't fit into sprite `{path}` ({width}x{height})")]
+    NineSliceTooLarge {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+    },
+
+    #[error("there are multiple sprites named `{path}`")]
+    DuplicateSprite { path: PathBuf },
+
+    #[error("sprites `{first}` and `{second}` would both have the id `{id}`")]
+    DuplicateSpriteId {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+
+    #[error("sprite `{sprite}` would have the id `{id}`, which is reserved")]
+    ReservedSpriteId { id: String, sprite: PathBuf },
+
+    #[error("there are multiple animations named `{name}`")]
+    DuplicateAnimation { name: String },
+
+    #[error("animation `{animation}` refers to sprite `{sprite}`, which doesn't exist")]
+    UnknownFrame { animation: String, sprite: PathBuf },
+
+    #[error("image `{path}` is empty")]
+    EmptyImage { path: PathBuf },
+
+    #[error(
+        "image `{path}` ({width}x{height}) is larger than the maximum page size {max_page_size}"
+    )]
+    ImageTooLarge {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+        max_page_size: u32,
+    },
+}
+
+/// hash of the decoded pixel data of a texture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextureId(u128);
+
+impl TextureId {
+    pub const HASH_SEED: u32 = 1312;
+
+    pub fn from_image(image: &RgbaImage) -> Self {
+        Self(utils::file_hash::murmur3_x64_128(
+            image.as_raw(),
+            Self::HASH_SEED,
+        ))
+    }
+}
+
+/// a unique image that was pushed into the [`AtlasBuilder`].
+struct Texture {
+    /// paths of the images and how they were trimmed. the paths are the keys
+    /// in the sprite sheet. if the same image was pushed multiple times, all
+    /// paths alias the same rect.
+    paths: Vec<(PathBuf, Option<Trim>)>,
+
+    /// the decoded image.
+    image: RgbaImage,
+
+    /// how the border around the image is filled.
+    padding: Padding,
+
+    /// whether the image may be rotated by 90° in the atlas.
+    allow_rotation: bool,
+}
+
+impl Texture {
+    /// size of the image including its padding.
+    fn padded_size(&self) -> Size {
+        let padding = 2 * self.padding.size();
+        Size::new(
+            (self.image.width() + padding) as i32,
+            (self.image.height() + padding) as i32,
+        )
+    }
+
+    /// whether we'd rather put the image into the atlas rotated. textures are
+    /// packed in rows sorted by height, so wide and flat textures pack better
+    /// than tall and thin ones.
+    fn prefers_rotation(&self) -> bool {
+        self.allow_rotation && self.image.height() > self.image.width()
+    }
+
+    /// size of the image including its padding, in the atlas.
+    fn packed_size(&self, rotated: bool) -> Size {
+        let size = self.padded_size();
+        if rotated {
+            Size::new(size.height, size.width)
+        }
+        else {
+            size
+        }
+    }
+}
+
+/// packs images into one or more atlas textures (pages).
+///
+/// images are only collected by [`AtlasBuilder::push_image`]. the actual
+/// packing is done in [`AtlasBuilder::build`], where we can sort them by size
+/// first, which packs a lot tighter than allocating them in the order they were
+/// pushed.
+///
+/// pages start small and are doubled in size when a texture doesn't fit. once
+/// all pages have reached the maximum page size, a new page is started.
+///
+/// each texture can have its own [`Padding`]. images can be trimmed before
+/// they're pushed, see [`trim`]. textures that allow it are rotated by 90°
+/// clockwise if that packs tighter.
+///
+/// images with identical pixel data are only stored once in the atlas. the
+/// sprites for all their paths point at the same rect.
+///
+/// optionally a normal map is derived for every texture, and put into a second
+/// set of pages with exactly the same layout.
+pub struct AtlasBuilder {
+    /// unique textures in the order they were pushed.
+    textures: Vec<Texture>,
+
+    /// names of all sprites that were pushed.
+    names: HashSet<PathBuf>,
+
+    /// indices into `textures`, by the hash of the pixel data and everything
+    /// else that changes how the texture ends up in the atlas.
+    texture_ids: HashMap<(TextureId, u32, u32, Padding, bool), usize>,
+
+    /// the size a page starts with.
+    initial_size: i32,
+
+    /// the maximum width and height of a page.
+    max_page_size: i32,
+
+    /// render normal map pages too, with the heights guessed like this.
+    normal_maps: Option<HeightSource>,
+}
+
+impl Default for AtlasBuilder {
+    fn default() -> Self {
+        Self {
+            textures: vec![],
+            names: HashSet::new(),
+            texture_ids: HashMap::new(),
+            initial_size: Self::DEFAULT_INITIAL_SIZE,
+            max_page_size: Self::DEFAULT_MAX_PAGE_SIZE,
+            normal_maps: None,
+        }
+    }
+}
+
+impl AtlasBuilder {
+    pub const DEFAULT_INITIAL_SIZE: i32 = 256;
+
+    /// `max_texture_dimension_2d` of
+    /// `wgpu::Limits::downlevel_webgl2_defaults()`, which is what the game
+    /// uses on wasm.
+    pub const DEFAULT_MAX_PAGE_SIZE: i32 = 2048;
+
+    pub fn with_max_page_size(mut self, max_page_size: u32) -> Self {
+        self.max_page_size = max_page_size as i32;
+        self.initial_size = self.initial_size.min(self.max_page_size);
+        self
+    }
+
+    pub fn with_normal_maps(mut self, source: HeightSource) -> Self {
+        self.normal_maps = Some(source);
+        self
+    }
+
+    pub fn push_image(
+        &mut self,
+        path: PathBuf,
+        image: RgbaImage,
+        padding: Padding,
+        trim: Option<Trim>,
+        allow_rotation: bool,
+    ) -> Result<(), Error> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(Error::EmptyImage { path });
+        }
+        if self.names.contains(&path) {
+            return Err(Error::DuplicateSprite { path });
+        }
+        self.names.insert(path.clone());
+
+        let key = (
+            TextureId::from_image(&image),
+            image.width(),
+            image.height(),
+            padding,
+            allow_rotation,
+        );
+        if let Some(index) = self.texture_ids.get(&key) {
+            self.textures[*index].paths.push((path, trim));
+            return Ok(());
+        }
+
+        let texture = Texture {
+            paths: vec![(path, trim)],
+            image,
+            padding,
+            allow_rotation,
+        };
+
+        let size = texture.padded_size();
+        if size.width > self.max_page_size || size.height > self.max_page_size {
+            return Err(Error::ImageTooLarge {
+                path: texture.paths.into_iter().next().unwrap().0,
+                width: size.width as u32,
+                height: size.height as u32,
+                max_page_size: self.max_page_size as u32,
+            });
+        }
+
+        self.texture_ids.insert(key, self.textures.len());
+        self.textures.push(texture);
+
+        Ok(())
+    }
+
+    /// iterates over all images that were pushed more than once. yields the
+    /// path of the image that was pushed first and the paths of its
+    /// duplicates.
+    pub fn duplicates(&self) -> impl Iterator<Item = (&Path, Vec<&Path>)> {
+        self.textures
+            .iter()
+            .filter(|texture| texture.paths.len() > 1)
+            .map(|texture| {
+                (
+                    texture.paths[0].0.as_path(),
+                    texture.paths[1..]
+                        .iter()
+                        .map(|(path, _)| path.as_path())
+                        .collect(),
+                )
+            })
+    }
+
+    /// finds space for a texture. returns the page index, the allocated
+    /// rectangle and whether the texture is rotated.
+    ///
+    /// if the texture can be rotated, we first try its preferred orientation
+    /// and then the other one, before we grow a page.
+    fn allocate(
+        &self,
+        pages: &mut Vec<AtlasAllocator>,
+        texture: &Texture,
+    ) -> (usize, Allocation, bool) {
+        let prefers_rotation = texture.prefers_rotation();
+        let mut orientations = vec![prefers_rotation];
+        if texture.allow_rotation {
+            orientations.push(!prefers_rotation);
+        }
+
+        loop {
+            for &rotated in &orientations {
+                let size = texture.packed_size(rotated);
+                for (page_index, page) in pages.iter_mut().enumerate() {
+                    if let Some(allocation) = page.allocate(size) {
+                        return (page_index, allocation, rotated);
+                    }
+                }
+            }
+
+            // it didn't fit anywhere. grow the first page that can still grow.
+            // growing keeps all existing allocations where they are. if all
+            // pages are at their maximum size, start a new page.
+            let max_page_size = Size::new(self.max_page_size, self.max_page_size);
+            if let Some(page) = pages.iter_mut().find(|page| page.size() != max_page_size) {
+                let new_size = (page.size() * 2).min(max_page_size);
+                log::debug!("resizing atlas page to: {:?}", new_size);
+                page.grow(new_size);
+            }
+            else {
+                log::debug!("starting atlas page {}", pages.len());
+                pages.push(AtlasAllocator::new(Size::new(
+                    self.initial_size,
+                    self.initial_size,
+                )));
+            }
+        }
+    }
+
+    /// packs all textures and renders the atlas pages.
+    pub fn build(self) -> Result<Atlas, Error> {
+        // allocate the largest textures first.
+        let mut order = (0..self.textures.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| {
+            let texture = &self.textures[*i];
+            let size = texture.packed_size(texture.prefers_rotation());
+            Reverse((size.height, size.width))
+        });
+
+        let mut pages = vec![];
+        let mut allocations = vec![None; self.textures.len()];
+        let mut regions = vec![];
+
+        for i in order {
+            let texture = &self.textures[i];
+            let (page, allocation, rotated) = self.allocate(&mut pages, texture);
+
+            regions.resize_with(pages.len(), Vec::new);
+            regions[page].push(allocation.rectangle.to_rect().to_u32());
+
+            // the sprite rect points at the unpadded content.
+            let padding = texture.padding.size() as i32;
+            let rect = allocation.rectangle.inflate(-padding, -padding);
+
+            allocations[i] = Some((page, rect.to_rect().to_u32(), rotated));
+        }
+
+        let mut page_textures = pages
+            .iter()
+            .map(|page| {
+                let size = page.size();
+                RgbaImage::new(size.width as u32, size.height as u32)
+            })
+            .collect::<Vec<_>>();
+        let mut normal_pages = if self.normal_maps.is_some() {
+            page_textures
+                .iter()
+                .map(|page| RgbaImage::from_pixel(page.width(), page.height(), normal_map::FLAT))
+                .collect()
+        }
+        else {
+            vec![]
+        };
+        let mut sprites = HashMap::new();
+
+        for (texture, allocation) in self.textures.into_iter().zip(allocations) {
+            let (page, rect, rotated) = allocation.expect("texture was not allocated");
+
+            // the normals are relative to the upright sprite, so only the
+            // pixels are rotated, not the normals themselves.
+            if let Some(source) = self.normal_maps {
+                let normal_map = normal_map::generate(&texture.image, source);
+                let normal_map = if rotated {
+                    imageops::rotate90(&normal_map)
+                }
+                else {
+                    normal_map
+                };
+                texture.padding.blit(
+                    &mut normal_pages[page],
+                    &normal_map,
+                    rect.origin.x,
+                    rect.origin.y,
+                );
+            }
+
+            let image = if rotated {
+                imageops::rotate90(&texture.image)
+            }
+            else {
+                texture.image
+            };
+            texture.padding.blit(
+                &mut page_textures[page],
+                &image,
+                rect.origin.x,
+                rect.origin.y,
+            );
+
+            for (path, trim) in texture.paths {
+                sprites.insert(
+                    path,
+                    Sprite {
+                        page,
+                        rect,
+                        trim,
+                        rotated,
+                        nine_slice: None,
+                        sdf: None,
+                    },
+                );
+            }
+        }
+
+        Ok(Atlas {
+            pages: page_textures,
+            normal_pages,
+            sprites,
+            regions,
+        })
+    }
+}
+
+/// a packed atlas, as returned by [`AtlasBuilder::build`].
+pub struct Atlas {
+    /// the rendered atlas pages.
+    pub pages: Vec<RgbaImage>,
+
+    /// the normal map for each page, if normal maps were enabled. empty
+    /// otherwise.
+    pub normal_pages: Vec<RgbaImage>,
+
+    /// the sprites by path.
+    pub sprites: HashMap<PathBuf, Sprite>,
+
+    /// for each page, the rects of all textures including their padding.
+    pub regions: Vec<Vec<Rect<u32>>>,
+}
+
+/// decodes an image.
+fn load_image(path: &Path, data: &[u8]) -> Result<RgbaImage, Error> {
+    let image = image::load_from_memory(data).map_err(|source| {
+        Error::Load {
+            path: path.to_owned(),
+            source,
+        }
+    })?;
+
+    Ok(image.into_rgba8())
+}
+
+/// path of the texture for an atlas page. if there is only one page, this is
+/// just `output_texture`, otherwise the page index is appended to the file
+/// stem, e.g. `atlas_1.png`.
+fn page_path(output_texture: &Path, page: usize, num_pages: usize) -> PathBuf {
+    if num_pages == 1 {
+        output_texture.to_owned()
+    }
+    else {
+        let mut file_name = output_texture.file_stem().unwrap_or_default().to_owned();
+        file_name.push(format!("_{}", page));
+        if let Some(extension) = output_texture.extension() {
+            file_name.push(".");
+            file_name.push(extension);
+        }
+        output_texture.with_file_name(file_name)
+    }
+}
+
+async fn write_png(path: &Path, image: &RgbaImage) -> Result<(), Error> {
+    let mut png = Cursor::new(vec![]);
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+    tokio::fs::write(path, png.into_inner()).await?;
+    Ok(())
+}
+
+/// options for how an input file is put into the atlas.
+#[derive(Clone, Debug, Default)]
+pub struct InputOptions {
+    pub padding: Padding,
+    pub slicing: Slicing,
+
+    /// remove transparent borders from the sprites.
+    pub trim: bool,
+
+    /// allow rotating the sprites by 90° in the atlas.
+    pub rotate: bool,
+
+    /// nine-slice insets for all sprites.
+    pub nine_slice: Option<NineSlice>,
+
+    /// convert the sprites into signed distance fields with this spread.
+    pub sdf: Option<NonZeroU32>,
+
+    /// sizes and characters, if the input is a font.
+    pub font: FontOptions,
+
+    /// turn numbered sprites like `flame_01.png`, `flame_02.png` into an
+    /// animation clip, with each frame shown this many milliseconds. see
+    /// [`animation`].
+    pub frame_duration: Option<NonZeroU32>,
+}
+
+/// options for a whole atlas.
+#[derive(Clone, Debug)]
+pub struct AtlasOptions {
+    /// maximum width and height of an atlas page.
+    pub max_page_size: u32,
+
+    /// write overlay images that show where the sprites are in the atlas
+    /// pages, and a text report with packing statistics.
+    pub debug: bool,
+
+    /// premultiply the color channels of the atlas pages with alpha.
+    pub premultiply: bool,
+
+    /// generate the mip levels of the atlas pages. see [`mipmap`].
+    pub mipmaps: bool,
+
+    /// generate normal map pages, with the heights guessed like this. see
+    /// [`normal_map`].
+    pub normal_maps: Option<HeightSource>,
+
+    /// write a rust module with typed ids for all sprites to this path. see
+    /// [`ids`].
+    pub output_sprite_ids: Option<PathBuf>,
+
+    /// animation clips with explicit frames, by name. these replace clips of
+    /// the same name from aseprite files or numbered sprites.
+    pub animations: BTreeMap<String, AnimationDefinition>,
+}
+
+/// a file that is packed into an atlas.
+#[derive(Clone, Debug)]
+pub struct Input {
+    /// where the file is loaded from.
+    pub path: PathBuf,
+
+    /// the sprite path in the sprite sheet. sprites sliced from the file, or
+    /// aseprite frames, are named after this.
+    pub name: PathBuf,
+
+    pub options: InputOptions,
+}
+
+impl Input {
+    /// an input that is named after its path.
+    pub fn new(path: PathBuf, options: InputOptions) -> Self {
+        Self {
+            name: path.clone(),
+            path,
+            options,
+        }
+    }
+}
+
+/// loads all `inputs`, packs them into an atlas and writes the atlas texture
+/// and sprite sheet. if `output_sprite_sheet` has the extension `bin`, the
+/// sprite sheet is written in the binary format, otherwise as json.
+///
+/// with [`AtlasOptions::normal_maps`], the normal maps are written next to
+/// the atlas pages, with `.normal` added to their names, e.g.
+/// `atlas.normal.png`.
+///
+/// with [`AtlasOptions::debug`], the overlay images are written next to the
+/// atlas pages, with `.debug` added to their names, e.g. `atlas.debug.png`.
+/// the report is written next to the sprite sheet, e.g. `atlas.report.txt`.
+///
+/// unless `force` is set, nothing is done if neither the inputs nor the
+/// options changed since the last build.
+pub async fn build(
+    output_texture: impl AsRef<Path>,
+    output_sprite_sheet: impl AsRef<Path>,
+    options: &AtlasOptions,
+    inputs: &[Input],
+    force: bool,
+) -> Result<(), Error> {
+    let output_texture = output_texture.as_ref();
+    let output_sprite_sheet = output_sprite_sheet.as_ref();
+
+    let mut cache = Cache::new(&(output_texture, output_sprite_sheet, options, inputs));
+    let mut input_data = Vec::with_capacity(inputs.len());
+
+    for input in inputs {
+        log::debug!("loading `{}`", input.path.display());
+
+        let data = tokio::fs::read(&input.path).await?;
+        cache.add_input(input.path.clone(), &data);
+        input_data.push(data);
+    }
+
+    if !cache.needs_rebuild(output_sprite_sheet, force).await {
+        return Ok(());
+    }
+
+    let mut builder = AtlasBuilder::default().with_max_page_size(options.max_page_size);
+    if let Some(source) = options.normal_maps {
+        builder = builder.with_normal_maps(source);
+    }
+    let mut animations = HashMap::new();
+    let mut nine_slices = HashMap::new();
+    let mut sdfs = HashMap::new();
+    let mut sequence_frames = vec![];
+
+    for (input, data) in inputs.iter().zip(input_data) {
+        let images = if aseprite::is_aseprite(&input.path) {
+            let aseprite = aseprite::load(&input.path, &input.name, &data)?;
+            for (name, animation) in aseprite.animations {
+                if animations.contains_key(&name) {
+                    return Err(Error::DuplicateAnimation { name });
+                }
+                animations.insert(name, animation);
+            }
+            aseprite.frames
+        }
+        else if font::is_font(&input.path) {
+            font::load(&input.path, &input.name, &data, &input.options.font)?.glyphs
+        }
+        else {
+            vec![(input.name.clone(), load_image(&input.path, &data)?)]
+        };
+
+        for (name, image) in images {
+            for (name, image) in input.options.slicing.slice(name, image)? {
+                if let Some(nine_slice) = input.options.nine_slice {
+                    if nine_slice.left + nine_slice.right > image.width()
+                        || nine_slice.top + nine_slice.bottom > image.height()
+                    {
+                        return Err(Error::NineSliceTooLarge {
+                            path: name,
+                            width: image.width(),
+                            height: image.height(),
+                        });
+                    }
+                    nine_slices.insert(name.clone(), nine_slice);
+                }
+
+                let (image, trim) = if input.options.trim {
+                    trim::trim(image)
+                }
+                else {
+                    (image, None)
+                };
+
+                let image = if let Some(spread) = input.options.sdf {
+                    let spread = spread.get();
+                    sdfs.insert(name.clone(), Sdf { spread });
+                    sdf::generate(&image, spread)
+                }
+                else {
+                    image
+                };
+
+                if let Some(duration) = input.options.frame_duration {
+                    sequence_frames.push((name.clone(), duration));
+                }
+
+                builder.push_image(
+                    name,
+                    image,
+                    input.options.padding,
+                    trim,
+                    input.options.rotate,
+                )?;
+            }
+        }
+    }
+
+    let duplicates = builder
+        .duplicates()
+        .map(|(original, duplicates)| {
+            (
+                original.to_owned(),
+                duplicates
+                    .into_iter()
+                    .map(Path::to_owned)
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    for (original, duplicates) in &duplicates {
+        log::info!("deduplicated `{}`:", original.display());
+        for duplicate in duplicates {
+            log::info!(" - `{}`", duplicate.display());
+        }
+    }
+
+    let Atlas {
+        pages: mut page_textures,
+        normal_pages: normal_page_textures,
+        mut sprites,
+        regions,
+    } = builder.build()?;
+    for (name, nine_slice) in nine_slices {
+        if let Some(sprite) = sprites.get_mut(&name) {
+            sprite.nine_slice = Some(nine_slice);
+        }
+    }
+    for (name, sdf) in sdfs {
+        if let Some(sprite) = sprites.get_mut(&name) {
+            sprite.sdf = Some(sdf);
+        }
+    }
+
+    for (name, animation) in animation::detect_sequences(&sequence_frames)? {
+        if animations.contains_key(&name) {
+            return Err(Error::DuplicateAnimation { name });
+        }
+        animations.insert(name, animation);
+    }
+    for (name, definition) in &options.animations {
+        animations.insert(name.clone(), definition.animation(name, &sprites)?);
+    }
+
+    // generate the ids before anything is written, so that e.g. duplicate ids
+    // don't leave outputs without a cache behind.
+    let sprite_ids = options
+        .output_sprite_ids
+        .as_ref()
+        .map(|path| {
+            ids::generate(output_sprite_sheet, sprites.keys()).map(|module| (path.clone(), module))
+        })
+        .transpose()?;
+
+    log::info!(
+        "packed {} sprites into {} atlas pages",
+        sprites.len(),
+        page_textures.len()
+    );
+
+    if options.premultiply {
+        for page_texture in &mut page_textures {
+            mipmap::premultiply(page_texture);
+        }
+    }
+
+    // page paths in the sprite sheet are relative to the sprite sheet.
+    let relative = |path: PathBuf| paths::relative_to_file(&path, output_sprite_sheet);
+
+    let mut pages = Vec::with_capacity(page_textures.len());
+    let mut mip_levels = vec![];
+    for (page, page_texture) in page_textures.iter().enumerate() {
+        let path = page_path(output_texture, page, page_textures.len());
+        log::debug!(
+            "writing {}x{} page to `{}`",
+            page_texture.width(),
+            page_texture.height(),
+            path.display()
+        );
+
+        write_png(&path, page_texture).await?;
+        cache.add_output(path.clone());
+
+        if options.mipmaps {
+            let levels = mipmap::mip_chain(page_texture, &regions[page], options.premultiply);
+            let mut level_paths = Vec::with_capacity(levels.len());
+
+            for (level, image) in levels.iter().enumerate() {
+                // level 0 is the page itself.
+                let level_path = path.with_extension(format!("mip{}.png", level + 1));
+                log::debug!("writing mip level to `{}`", level_path.display());
+
+                write_png(&level_path, image).await?;
+                cache.add_output(level_path.clone());
+                level_paths.push(relative(level_path));
+            }
+
+            mip_levels.push(level_paths);
+        }
+
+        pages.push(relative(path));
+    }
+
+    let mut normal_pages = Vec::with_capacity(normal_page_textures.len());
+    for (page, normal_page_texture) in normal_page_textures.iter().enumerate() {
+        let path = page_path(output_texture, page, normal_page_textures.len())
+            .with_extension("normal.png");
+        log::debug!("writing normal map to `{}`", path.display());
+
+        write_png(&path, normal_page_texture).await?;
+        cache.add_output(path.clone());
+        normal_pages.push(relative(path));
+    }
+
+    if options.debug {
+        let debug_output = DebugOutput::new(&sprites);
+
+        for (page, page_texture) in page_textures.iter().enumerate() {
+            let path =
+                page_path(output_texture, page, page_textures.len()).with_extension("debug.png");
+            log::info!("writing debug overlay to `{}`", path.display());
+
+            write_png(&path, &debug_output.overlay(page, page_texture)).await?;
+            cache.add_output(path);
+        }
+
+        let page_sizes = page_textures
+            .iter()
+            .map(|page_texture| page_texture.dimensions())
+            .collect::<Vec<_>>();
+        let report = debug_output.report(output_texture, &page_sizes, &duplicates);
+        let path = output_sprite_sheet.with_extension("report.txt");
+        log::info!("writing debug report to `{}`", path.display());
+
+        tokio::fs::write(&path, report).await?;
+        cache.add_output(path);
+    }
+
+    let sprite_sheet = SpriteSheet {
+        pages,
+        mip_levels,
+        normal_pages,
+        premultiplied_alpha: options.premultiply,
+        sprites,
+        animations,
+    };
+
+    // the sprite sheet is written as json, unless it should be binary.
+    let data = if output_sprite_sheet
+        .extension()
+        .is_some_and(|extension| extension == "bin")
+    {
+        sprite_sheet.to_binary()?
+    }
+    else {
+        serde_json::to_vec_pretty(&sprite_sheet)?
+    };
+    tokio::fs::write(output_sprite_sheet, data).await?;
+    cache.add_output(output_sprite_sheet.to_owned());
+
+    if let Some((path, module)) = sprite_ids {
+        log::info!("writing sprite ids to `{}`", path.display());
+
+        tokio::fs::write(&path, module).await?;
+        cache.add_output(path);
+    }
+
+    cache.save(output_sprite_sheet).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use euclid::default::{
+        Point2D,
+        Size2D,
+        Vector2D,
+    };
+    use image::Rgba;
+
+    use super::*;
+
+    /// an image with a single color, so that every image we push is unique.
+    fn image(width: u32, height: u32, color: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([color, 0, 0, 255]))
+    }
+
+    fn push(builder: &mut AtlasBuilder, name: &str, image: RgbaImage) {
+        builder
+            .push_image(name.into(), image, Padding::None, None, false)
+            .unwrap();
+    }
+
+    fn sprite<'a>(atlas: &'a Atlas, name: &str) -> &'a Sprite {
+        &atlas.sprites[Path::new(name)]
+    }
+
+    #[test]
+    fn pages_grow_up_to_the_max_page_size() {
+        let mut builder = AtlasBuilder::default().with_max_page_size(1024);
+        push(&mut builder, "a.png", image(16, 16, 1));
+        push(&mut builder, "b.png", image(300, 16, 2));
+
+        let atlas = builder.build().unwrap();
+        assert_eq!(atlas.pages.len(), 1);
+        assert_eq!(atlas.pages[0].dimensions(), (512, 512));
+
+        let a = sprite(&atlas, "a.png");
+        let b = sprite(&atlas, "b.png");
+        assert_eq!(a.rect.size, Size2D::new(16, 16));
+        assert_eq!(b.rect.size, Size2D::new(300, 16));
+        assert!(!a.rect.intersects(&b.rect));
+    }
+
+    #[test]
+    fn full_pages_are_split() {
+        let mut builder = AtlasBuilder::default().with_max_page_size(32);
+        for i in 0..5 {
+            push(&mut builder, &format!("{}.png", i), image(16, 16, i));
+        }
+
+        let atlas = builder.build().unwrap();
+        assert_eq!(atlas.pages.len(), 2);
+        assert_eq!(atlas.regions[0].len(), 4);
+        assert_eq!(atlas.regions[1].len(), 1);
+
+        for i in 0..5 {
+            let sprite = sprite(&atlas, &format!("{}.png", i));
+            let page = &atlas.pages[sprite.page];
+            assert_eq!(page.dimensions(), (32, 32));
+            assert_eq!(
+                *page.get_pixel(sprite.rect.origin.x, sprite.rect.origin.y),
+                Rgba([i, 0, 0, 255])
+            );
+            assert_eq!(
+                *page.get_pixel(sprite.rect.max_x() - 1, sprite.rect.max_y() - 1),
+                Rgba([i, 0, 0, 255])
+            );
+        }
+    }
+
+    #[test]
+    fn images_larger_than_a_page_are_rejected() {
+        let mut builder = AtlasBuilder::default().with_max_page_size(32);
+        let result = builder.push_image(
+            "large.png".into(),
+            image(31, 8, 1),
+            Padding::Extrude(1),
+            None,
+            false,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::ImageTooLarge {
+                width: 33,
+                height: 10,
+                max_page_size: 32,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn trim_and_padding() {
+        // an 8x8 image with a 3x4 opaque block at (2, 3), with a different
+        // color in its top-left corner.
+        let mut original = RgbaImage::new(8, 8);
+        for y in 3..7 {
+            for x in 2..5 {
+                original.put_pixel(x, y, Rgba([1, 0, 0, 255]));
+            }
+        }
+        original.put_pixel(2, 3, Rgba([2, 0, 0, 255]));
+
+        let (trimmed, trim) = trim::trim(original);
+        assert_eq!(trimmed.dimensions(), (3, 4));
+        assert_eq!(
+            trim,
+            Some(Trim {
+                offset: Vector2D::new(2, 3),
+                original_size: Size2D::new(8, 8),
+            })
+        );
+
+        let mut builder = AtlasBuilder::default();
+        builder
+            .push_image(
+                "extruded.png".into(),
+                trimmed.clone(),
+                Padding::Extrude(2),
+                trim,
+                false,
+            )
+            .unwrap();
+        builder
+            .push_image(
+                "transparent.png".into(),
+                trimmed,
+                Padding::Transparent(2),
+                trim,
+                false,
+            )
+            .unwrap();
+        let atlas = builder.build().unwrap();
+        let page = &atlas.pages[0];
+
+        for name in ["extruded.png", "transparent.png"] {
+            let sprite = sprite(&atlas, name);
+            assert_eq!(sprite.rect.size, Size2D::new(3, 4));
+            assert_eq!(sprite.size(), Size2D::new(3, 4));
+            assert_eq!(sprite.offset(), Vector2D::new(2, 3));
+            assert_eq!(sprite.original_size(), Size2D::new(8, 8));
+
+            // the region includes the padding, the sprite rect doesn't.
+            let padded = sprite.rect.inflate(2, 2);
+            assert!(atlas.regions[0].contains(&padded));
+            assert_eq!(
+                *page.get_pixel(sprite.rect.origin.x, sprite.rect.origin.y),
+                Rgba([2, 0, 0, 255])
+            );
+        }
+
+        // extruded padding repeats the corner, transparent padding is empty.
+        let extruded = sprite(&atlas, "extruded.png").rect.origin;
+        let transparent = sprite(&atlas, "transparent.png").rect.origin;
+        assert_eq!(
+            *page.get_pixel(extruded.x - 2, extruded.y - 2),
+            Rgba([2, 0, 0, 255])
+        );
+        assert_eq!(
+            *page.get_pixel(transparent.x - 1, transparent.y - 1),
+            Rgba([0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn rotation() {
+        // a tall image with a different color in its top-left corner.
+        let mut tall = image(4, 8, 1);
+        tall.put_pixel(0, 0, Rgba([2, 0, 0, 255]));
+
+        let mut builder = AtlasBuilder::default();
+        builder
+            .push_image(
+                "rotated.png".into(),
+                tall.clone(),
+                Padding::None,
+                None,
+                true,
+            )
+            .unwrap();
+        builder
+            .push_image("upright.png".into(), tall, Padding::None, None, false)
+            .unwrap();
+        let atlas = builder.build().unwrap();
+        let page = &atlas.pages[0];
+        let page_size = Size2D::new(page.width(), page.height());
+
+        let upright = sprite(&atlas, "upright.png");
+        assert!(!upright.rotated);
+        assert_eq!(upright.rect.size, Size2D::new(4, 8));
+
+        let rotated = sprite(&atlas, "rotated.png");
+        assert!(rotated.rotated);
+        assert_eq!(rotated.rect.size, Size2D::new(8, 4));
+        assert_eq!(rotated.size(), Size2D::new(4, 8));
+
+        // the top-left corner of the image ends up in the top-right corner of
+        // the rect, and that's where the tex-coords of the top-left corner
+        // point.
+        let corner = Point2D::new(rotated.rect.max_x() - 1, rotated.rect.min_y());
+        assert_eq!(*page.get_pixel(corner.x, corner.y), Rgba([2, 0, 0, 255]));
+
+        let [top_left, bottom_left, bottom_right, top_right] = rotated.tex_coords(page_size);
+        let u = |x: u32| x as f32 / page_size.width as f32;
+        let v = |y: u32| y as f32 / page_size.height as f32;
+        assert_eq!(top_left, [u(rotated.rect.max_x()), v(rotated.rect.min_y())]);
+        assert_eq!(
+            bottom_left,
+            [u(rotated.rect.min_x()), v(rotated.rect.min_y())]
+        );
+        assert_eq!(
+            bottom_right,
+            [u(rotated.rect.min_x()), v(rotated.rect.max_y())]
+        );
+        assert_eq!(
+            top_right,
+            [u(rotated.rect.max_x()), v(rotated.rect.max_y())]
+        );
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let mut builder = AtlasBuilder::default();
+        push(&mut builder, "a.png", image(4, 4, 1));
+
+        let result = builder.push_image("a.png".into(), image(4, 4, 2), Padding::None, None, false);
+        assert!(matches!(
+            result,
+            Err(Error::DuplicateSprite { path }) if path == Path::new("a.png")
+        ));
+    }
+
+    #[test]
+    fn identical_images_are_stored_once() {
+        let mut builder = AtlasBuilder::default();
+        push(&mut builder, "a.png", image(4, 4, 1));
+        push(&mut builder, "b.png", image(4, 4, 1));
+        push(&mut builder, "c.png", image(4, 4, 2));
+
+        assert_eq!(
+            builder.duplicates().collect::<Vec<_>>(),
+            [(Path::new("a.png"), vec![Path::new("b.png")])]
+        );
+
+        let atlas = builder.build().unwrap();
+        assert_eq!(atlas.regions[0].len(), 2);
+        assert_eq!(sprite(&atlas, "a.png"), sprite(&atlas, "b.png"));
+        assert_ne!(sprite(&atlas, "a.png").rect, sprite(&atlas, "c.png").rect);
+    }
+
+    #[test]
+    fn empty_images_are_rejected() {
+        let mut builder = AtlasBuilder::default();
+        let result = builder.push_image(
+            "empty.png".into(),
+            RgbaImage::new(0, 4),
+            Padding::None,
+            None,
+            false,
+        );
+        assert!(matches!(result, Err(Error::EmptyImage { .. })));
+    }
+}
@@ -0,0 +1,116 @@
+//! normal maps for 2D lighting.
+//!
+//! nobody paints normal maps for pixel art, so we derive them from a height
+//! map that is guessed from the sprite itself. the normals are computed on the
+//! upright sprite, before it's rotated into the atlas, so they're always
+//! relative to the sprite as it's drawn.
+//!
+//! normals are encoded as `(n + 1) / 2` in the color channels, with green
+//! pointing up, like in opengl. the alpha channel is opaque.
+
+use std::str::FromStr;
+
+use image::{
+    Rgba,
+    RgbaImage,
+};
+use serde::{
+    de,
+    Deserialize,
+    Deserializer,
+};
+use thiserror::Error;
+
+/// a normal pointing straight out of the screen. this is what the normal map
+/// pages are filled with where there are no sprites.
+pub const FLAT: Rgba<u8> = Rgba([128, 128, 255, 255]);
+
+/// how steep the slopes are for a height difference of 1 between neighbouring
+/// pixels.
+const STRENGTH: f32 = 2.0;
+
+/// how the height of a pixel is guessed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HeightSource {
+    /// brighter pixels are higher. works well for textured tiles.
+    Luminance,
+
+    /// opaque pixels are higher than transparent ones, so only the outline of
+    /// the sprite is rounded. works well for flat-shaded sprites.
+    Alpha,
+}
+
+impl HeightSource {
+    fn height(&self, pixel: &Rgba<u8>) -> f32 {
+        let [r, g, b, a] = pixel.0.map(|channel| f32::from(channel) / 255.0);
+        match self {
+            // rec. 709 luma
+            HeightSource::Luminance => (0.2126 * r + 0.7152 * g + 0.0722 * b) * a,
+            HeightSource::Alpha => a,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("invalid normal map height source `{0}`: expected `luminance` or `alpha`")]
+pub struct ParseHeightSourceError(String);
+
+impl FromStr for HeightSource {
+    type Err = ParseHeightSourceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "luminance" => Ok(HeightSource::Luminance),
+            "alpha" => Ok(HeightSource::Alpha),
+            _ => Err(ParseHeightSourceError(s.to_owned())),
+        }
+    }
+}
+
+/// deserializes from the same strings as [`HeightSource::from_str`].
+impl<'de> Deserialize<'de> for HeightSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// derives a normal map for `image`. the result has the same size.
+///
+/// the gradient of the height map is computed with a sobel filter. pixels at
+/// the border of the image are repeated, so neighbouring sprites in the atlas
+/// don't affect each other.
+pub fn generate(image: &RgbaImage, source: HeightSource) -> RgbaImage {
+    let width = image.width() as i64;
+    let height = image.height() as i64;
+
+    let heights = image
+        .pixels()
+        .map(|pixel| source.height(pixel))
+        .collect::<Vec<_>>();
+    let height_at = |x: i64, y: i64| {
+        let x = x.clamp(0, width - 1);
+        let y = y.clamp(0, height - 1);
+        heights[(y * width + x) as usize]
+    };
+
+    let mut normal_map = RgbaImage::new(image.width(), image.height());
+
+    for (x, y, pixel) in normal_map.enumerate_pixels_mut() {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let h = |dx: i64, dy: i64| height_at(x + dx, y + dy);
+
+        let dx = (h(1, -1) + 2.0 * h(1, 0) + h(1, 1)) - (h(-1, -1) + 2.0 * h(-1, 0) + h(-1, 1));
+        let dy = (h(-1, 1) + 2.0 * h(0, 1) + h(1, 1)) - (h(-1, -1) + 2.0 * h(0, -1) + h(1, -1));
+
+        // the sobel kernels sum up to 4 times the gradient. y points down in
+        // the image, but up in the normal map.
+        let normal = [-dx / 4.0 * STRENGTH, dy / 4.0 * STRENGTH, 1.0];
+        let length = normal.iter().map(|n| n * n).sum::<f32>().sqrt();
+        let [r, g, b] = normal.map(|n| ((n / length + 1.0) / 2.0 * 255.0).round() as u8);
+
+        *pixel = Rgba([r, g, b, 255]);
+    }
+
+    normal_map
+}
@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use image::RgbaImage;
+use serde::{
+    de,
+    Deserialize,
+    Deserializer,
+};
+use thiserror::Error;
+
+/// how the border around a sprite in the atlas is filled.
+///
+/// with nearest filtering and sub-pixel camera movement, texture lookups at
+/// the edge of a sprite can hit the neighbouring sprite in the atlas[1]. a
+/// padded border around each sprite prevents this. the sprite rect in the
+/// sprite sheet always points at the unpadded content.
+///
+/// [1]: https://stackoverflow.com/questions/16050574/hlsl-color-bleeding-when-using-a-texture-atlas
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// sprites are packed right next to each other.
+    #[default]
+    None,
+
+    /// transparent gutter of the given width.
+    Transparent(u32),
+
+    /// border of the given width that repeats the edge pixels of the sprite.
+    Extrude(u32),
+
+    /// border of the given width that wraps around to the opposite edge of the
+    /// sprite. use this for tiling textures.
+    Tiled(u32),
+}
+
+impl Padding {
+    /// width of the border on each side of the sprite.
+    pub fn size(&self) -> u32 {
+        match self {
+            Padding::None => 0,
+            Padding::Transparent(size) | Padding::Extrude(size) | Padding::Tiled(size) => *size,
+        }
+    }
+
+    /// copies `image` and its padding into `target`. `x` and `y` are the
+    /// position of the unpadded content, so the padding is written to the
+    /// left and top of it.
+    pub fn blit(&self, target: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32) {
+        let size = self.size() as i64;
+        let width = image.width() as i64;
+        let height = image.height() as i64;
+
+        for dy in -size..height + size {
+            for dx in -size..width + size {
+                let source = match self {
+                    Padding::None | Padding::Transparent(_) => {
+                        // the atlas is transparent already, so we only copy the content.
+                        ((0..width).contains(&dx) && (0..height).contains(&dy)).then_some((dx, dy))
+                    }
+                    Padding::Extrude(_) => Some((dx.clamp(0, width - 1), dy.clamp(0, height - 1))),
+                    Padding::Tiled(_) => Some((dx.rem_euclid(width), dy.rem_euclid(height))),
+                };
+
+                if let Some((sx, sy)) = source {
+                    target.put_pixel(
+                        (x as i64 + dx) as u32,
+                        (y as i64 + dy) as u32,
+                        *image.get_pixel(sx as u32, sy as u32),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("invalid padding `{0}`: expected `none`, `transparent`, `extrude` or `tiled`, optionally followed by `:<pixels>`")]
+pub struct ParsePaddingError(String);
+
+impl FromStr for Padding {
+    type Err = ParsePaddingError;
+
+    /// parses e.g. `none`, `extrude` or `tiled:2`. the size defaults to 1
+    /// pixel.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mode, size) = match s.split_once(':') {
+            Some((mode, size)) => {
+                let size = size.parse().map_err(|_| ParsePaddingError(s.to_owned()))?;
+                (mode, size)
+            }
+            None => (s, 1),
+        };
+
+        match mode {
+            "none" => Ok(Padding::None),
+            "transparent" => Ok(Padding::Transparent(size)),
+            "extrude" => Ok(Padding::Extrude(size)),
+            "tiled" => Ok(Padding::Tiled(size)),
+            _ => Err(ParsePaddingError(s.to_owned())),
+        }
+    }
+}
+
+/// deserializes from the same strings as [`Padding::from_str`].
+impl<'de> Deserialize<'de> for Padding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,58 @@
+//! signed distance fields.
+//!
+//! a sprite is converted into a single-channel distance field, stored in the
+//! alpha channel. see [`assets::sprite_sheet::Sdf`] for how it's encoded.
+//! pixels with an alpha of at least 0.5 are inside of the shape.
+
+use image::{
+    Rgba,
+    RgbaImage,
+};
+
+/// converts `image` into a signed distance field. the result is larger than
+/// `image` by `spread` pixels on every side.
+///
+/// for every pixel we look for the closest pixel on the other side of the edge,
+/// but only within `spread`. that's a lot of lookups, but emotes and icons are
+/// small, and it's exact.
+pub fn generate(image: &RgbaImage, spread: u32) -> RgbaImage {
+    let spread = i64::from(spread);
+    let width = i64::from(image.width());
+    let height = i64::from(image.height());
+
+    // everything outside of the image is outside of the shape.
+    let inside = |x: i64, y: i64| {
+        (0..width).contains(&x)
+            && (0..height).contains(&y)
+            && image.get_pixel(x as u32, y as u32)[3] >= 128
+    };
+
+    let mut sdf = RgbaImage::new((width + 2 * spread) as u32, (height + 2 * spread) as u32);
+
+    for (sdf_x, sdf_y, pixel) in sdf.enumerate_pixels_mut() {
+        let x = i64::from(sdf_x) - spread;
+        let y = i64::from(sdf_y) - spread;
+        let is_inside = inside(x, y);
+
+        // squared distance to the closest pixel on the other side. anything
+        // further than the spread is clamped anyway.
+        let mut closest = (spread + 1) * (spread + 1);
+        for dy in -spread - 1..=spread + 1 {
+            for dx in -spread - 1..=spread + 1 {
+                let distance = dx * dx + dy * dy;
+                if distance < closest && inside(x + dx, y + dy) != is_inside {
+                    closest = distance;
+                }
+            }
+        }
+
+        // the edge is halfway between the pixel centers.
+        let distance = ((closest as f32).sqrt() - 0.5).min(spread as f32);
+        let signed = if is_inside { distance } else { -distance };
+
+        let alpha = (0.5 + signed / (2.0 * spread as f32)).clamp(0.0, 1.0);
+        *pixel = Rgba([255, 255, 255, (alpha * 255.0).round() as u8]);
+    }
+
+    sdf
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::BTreeMap,
+    num::NonZeroU32,
+    path::PathBuf,
+};
+
+use euclid::default::Rect;
+use image::{
+    imageops,
+    RgbaImage,
+};
+use serde::Deserialize;
+
+use crate::sprite_sheet::Error;
+
+/// a regular grid of tiles in a sheet.
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct Grid {
+    pub tile_width: NonZeroU32,
+    pub tile_height: NonZeroU32,
+
+    /// space around the whole grid.
+    #[serde(default)]
+    pub margin: u32,
+
+    /// space between neighbouring tiles.
+    #[serde(default)]
+    pub spacing: u32,
+}
+
+/// how an image is cut into multiple sprites.
+#[derive(Clone, Debug, Default)]
+pub enum Slicing {
+    /// the whole image is a single sprite.
+    #[default]
+    None,
+
+    /// cuts the image into tiles named `<path>#<index>`, counting row by row.
+    /// the index matches the tile ids that e.g. Tiled uses for a tileset.
+    /// fully transparent tiles are skipped.
+    Grid(Grid),
+
+    /// cuts the image into the given rects, named `<path>#<name>`.
+    Rects(BTreeMap<String, Rect<u32>>),
+}
+
+impl Slicing {
+    /// cuts `image` into sprites. returns the sprite paths and images.
+    pub fn slice(
+        &self,
+        path: PathBuf,
+        image: RgbaImage,
+    ) -> Result<Vec<(PathBuf, RgbaImage)>, Error> {
+        let sub_path = |name: &dyn std::fmt::Display| {
+            let mut sub_path = path.as_os_str().to_owned();
+            sub_path.push(format!("#{}", name));
+            PathBuf::from(sub_path)
+        };
+
+        match self {
+            Slicing::None => Ok(vec![(path, image)]),
+            Slicing::Grid(grid) => {
+                let tile_width = grid.tile_width.get();
+                let tile_height = grid.tile_height.get();
+                let columns = tile_count(image.width(), tile_width, grid.margin, grid.spacing);
+                let rows = tile_count(image.height(), tile_height, grid.margin, grid.spacing);
+
+                // the tiles that fit are inside the image, so their positions fit
+                // into a `u32`, but the steps between them might not.
+                let position = |index: u32, tile: u32| {
+                    (grid.margin as u64 + index as u64 * (tile as u64 + grid.spacing as u64)) as u32
+                };
+
+                let mut tiles = vec![];
+                for row in 0..rows {
+                    for column in 0..columns {
+                        let tile = imageops::crop_imm(
+                            &image,
+                            position(column, tile_width),
+                            position(row, tile_height),
+                            tile_width,
+                            tile_height,
+                        )
+                        .to_image();
+
+                        if tile.pixels().any(|pixel| pixel[3] != 0) {
+                            tiles.push((sub_path(&(row * columns + column)), tile));
+                        }
+                    }
+                }
+
+                Ok(tiles)
+            }
+            Slicing::Rects(rects) => {
+                rects
+                    .iter()
+                    .map(|(name, rect)| {
+                        let max_x = rect.origin.x.checked_add(rect.size.width);
+                        let max_y = rect.origin.y.checked_add(rect.size.height);
+                        if max_x.is_none_or(|max_x| max_x > image.width())
+                            || max_y.is_none_or(|max_y| max_y > image.height())
+                        {
+                            return Err(Error::SliceOutOfBounds {
+                                path: path.clone(),
+                                name: name.clone(),
+                            });
+                        }
+
+                        let slice = imageops::crop_imm(
+                            &image,
+                            rect.origin.x,
+                            rect.origin.y,
+                            rect.size.width,
+                            rect.size.height,
+                        )
+                        .to_image();
+
+                        Ok((sub_path(name), slice))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// how many tiles of size `tile` fit into `size`. this is computed with 64
+/// bits, so that large margins and spacings from a manifest can't overflow.
+fn tile_count(size: u32, tile: u32, margin: u32, spacing: u32) -> u32 {
+    let (size, tile, margin, spacing) = (size as u64, tile as u64, margin as u64, spacing as u64);
+    // at most `size`, so it fits into a `u32`.
+    ((size.saturating_sub(2 * margin) + spacing) / (tile + spacing)) as u32
+}
@@ -0,0 +1,45 @@
+use assets::sprite_sheet::Trim;
+use euclid::default::{
+    Size2D,
+    Vector2D,
+};
+use image::{
+    imageops,
+    RgbaImage,
+};
+
+/// removes fully transparent rows and columns around `image`. returns the
+/// trimmed image and how it was trimmed, or the image itself if there is
+/// nothing to trim.
+///
+/// fully transparent images are kept as they are, since we can't put empty
+/// images into the atlas.
+pub fn trim(image: RgbaImage) -> (RgbaImage, Option<Trim>) {
+    let mut min = (u32::MAX, u32::MAX);
+    let mut max = (0, 0);
+
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] != 0 {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+    }
+
+    if min.0 > max.0 {
+        return (image, None);
+    }
+
+    let width = max.0 - min.0 + 1;
+    let height = max.1 - min.1 + 1;
+    if width == image.width() && height == image.height() {
+        return (image, None);
+    }
+
+    let trimmed = imageops::crop_imm(&image, min.0, min.1, width, height).to_image();
+    let trim = Trim {
+        offset: Vector2D::new(min.0, min.1),
+        original_size: Size2D::new(image.width(), image.height()),
+    };
+
+    (trimmed, Some(trim))
+}
@@ -0,0 +1,289 @@
+//! checks that a sprite sheet is consistent with its atlas pages.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use euclid::default::{
+    Rect,
+    Size2D,
+};
+use thiserror::Error;
+
+use crate::{
+    cache::Cache,
+    sprite_sheet::{
+        self,
+        LoadError,
+    },
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    SpriteSheet(#[from] LoadError),
+}
+
+/// something that is wrong with a sprite sheet.
+#[derive(Debug, Error)]
+pub enum Problem {
+    #[error("the sprite sheet doesn't list its atlas pages. it needs to be rebuilt.")]
+    NoPages,
+
+    #[error("page `{path}` can't be loaded: {source}")]
+    InvalidPage {
+        path: PathBuf,
+        #[source]
+        source: image::ImageError,
+    },
+
+    #[error("there are {num_normal_pages} normal map pages, but {num_pages} atlas pages")]
+    NormalPageCount {
+        num_normal_pages: usize,
+        num_pages: usize,
+    },
+
+    #[error(
+        "normal map `{path}` is {}x{}, but its atlas page is {}x{}",
+        size.width,
+        size.height,
+        page_size.width,
+        page_size.height
+    )]
+    NormalPageSize {
+        path: PathBuf,
+        size: Size2D<u32>,
+        page_size: Size2D<u32>,
+    },
+
+    #[error("sprite `{sprite}` is on page {page}, but there are only {num_pages} pages")]
+    PageOutOfRange {
+        sprite: PathBuf,
+        page: usize,
+        num_pages: usize,
+    },
+
+    #[error("sprite `{sprite}` is empty")]
+    EmptyRect { sprite: PathBuf },
+
+    #[error(
+        "sprite `{sprite}` ({}) is outside of page {page} ({}x{})",
+        fmt_rect(rect),
+        page_size.width,
+        page_size.height
+    )]
+    OutOfBounds {
+        sprite: PathBuf,
+        rect: Rect<u32>,
+        page: usize,
+        page_size: Size2D<u32>,
+    },
+
+    #[error(
+        "sprites `{first}` ({}) and `{second}` ({}) overlap on page {page}",
+        fmt_rect(first_rect),
+        fmt_rect(second_rect)
+    )]
+    Overlap {
+        first: PathBuf,
+        first_rect: Rect<u32>,
+        second: PathBuf,
+        second_rect: Rect<u32>,
+        page: usize,
+    },
+
+    #[error("sprite `{sprite}` is trimmed, but doesn't fit into its original size")]
+    InvalidTrim { sprite: PathBuf },
+
+    #[error("the nine-slice insets of sprite `{sprite}` don't fit into the sprite")]
+    InvalidNineSlice { sprite: PathBuf },
+
+    #[error("animation `{animation}` refers to sprite `{sprite}`, which doesn't exist")]
+    MissingFrame { animation: String, sprite: PathBuf },
+
+    #[error("source file `{path}` doesn't exist anymore")]
+    MissingSource { path: PathBuf },
+}
+
+fn fmt_rect(rect: &Rect<u32>) -> String {
+    format!(
+        "{}x{} at ({}, {})",
+        rect.size.width, rect.size.height, rect.origin.x, rect.origin.y
+    )
+}
+
+/// whether `offset + size` is at most `max`. the sprite sheet might be
+/// corrupted, so the sum can overflow, which doesn't fit either.
+fn fits(offset: u32, size: u32, max: u32) -> bool {
+    offset.checked_add(size).is_some_and(|end| end <= max)
+}
+
+/// checks the sprite sheet at `path` and returns everything that's wrong
+/// with it.
+///
+/// this checks that all sprites are inside of their atlas page and don't
+/// overlap, that the normal maps match the pages, that the trim and nine-slice
+/// metadata is consistent, and that animations only refer to existing sprites.
+/// if the cache of the last build exists, the source files that the sprite
+/// sheet was built from must still exist as well.
+pub async fn validate(path: &Path) -> Result<Vec<Problem>, Error> {
+    let sprite_sheet = sprite_sheet::load(path).await?;
+    let mut problems = vec![];
+
+    // page paths are relative to the sprite sheet.
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    if sprite_sheet.pages.is_empty() {
+        problems.push(Problem::NoPages);
+    }
+
+    let mut page_sizes = Vec::with_capacity(sprite_sheet.pages.len());
+    for page in &sprite_sheet.pages {
+        let path = dir.join(page);
+        match image::image_dimensions(&path) {
+            Ok((width, height)) => page_sizes.push(Some(Size2D::new(width, height))),
+            Err(source) => {
+                problems.push(Problem::InvalidPage { path, source });
+                page_sizes.push(None);
+            }
+        }
+    }
+
+    // the normal maps must line up with the pages exactly.
+    if !sprite_sheet.normal_pages.is_empty()
+        && sprite_sheet.normal_pages.len() != sprite_sheet.pages.len()
+    {
+        problems.push(Problem::NormalPageCount {
+            num_normal_pages: sprite_sheet.normal_pages.len(),
+            num_pages: sprite_sheet.pages.len(),
+        });
+    }
+    for (normal_page, page_size) in sprite_sheet.normal_pages.iter().zip(&page_sizes) {
+        let path = dir.join(normal_page);
+        match image::image_dimensions(&path) {
+            Ok((width, height)) => {
+                let size = Size2D::new(width, height);
+                if let Some(page_size) = page_size.filter(|page_size| *page_size != size) {
+                    problems.push(Problem::NormalPageSize {
+                        path,
+                        size,
+                        page_size,
+                    });
+                }
+            }
+            Err(source) => problems.push(Problem::InvalidPage { path, source }),
+        }
+    }
+
+    // sort the sprites, so that the problems are reported in a stable order.
+    let mut sprites = sprite_sheet.sprites.iter().collect::<Vec<_>>();
+    sprites.sort_by_key(|(path, _)| *path);
+
+    for (path, sprite) in &sprites {
+        let rect = sprite.rect;
+
+        if rect.is_empty() {
+            problems.push(Problem::EmptyRect {
+                sprite: path.to_path_buf(),
+            });
+        }
+
+        match page_sizes.get(sprite.page) {
+            None => {
+                problems.push(Problem::PageOutOfRange {
+                    sprite: path.to_path_buf(),
+                    page: sprite.page,
+                    num_pages: page_sizes.len(),
+                });
+            }
+            Some(Some(page_size)) => {
+                if !fits(rect.origin.x, rect.size.width, page_size.width)
+                    || !fits(rect.origin.y, rect.size.height, page_size.height)
+                {
+                    problems.push(Problem::OutOfBounds {
+                        sprite: path.to_path_buf(),
+                        rect,
+                        page: sprite.page,
+                        page_size: *page_size,
+                    });
+                }
+            }
+            // the page couldn't be loaded, which was already reported.
+            Some(None) => {}
+        }
+
+        let size = sprite.size();
+        if let Some(trim) = sprite.trim {
+            if !fits(trim.offset.x, size.width, trim.original_size.width)
+                || !fits(trim.offset.y, size.height, trim.original_size.height)
+            {
+                problems.push(Problem::InvalidTrim {
+                    sprite: path.to_path_buf(),
+                });
+            }
+        }
+
+        if let Some(nine_slice) = sprite.nine_slice {
+            let original_size = sprite.original_size();
+            if !fits(nine_slice.left, nine_slice.right, original_size.width)
+                || !fits(nine_slice.top, nine_slice.bottom, original_size.height)
+            {
+                problems.push(Problem::InvalidNineSlice {
+                    sprite: path.to_path_buf(),
+                });
+            }
+        }
+    }
+
+    // deduplicated sprites share the same rect, so only different rects can
+    // overlap. the rects are compared with 64 bits, since rects that are out
+    // of bounds might overflow.
+    for (i, (first, first_sprite)) in sprites.iter().enumerate() {
+        for (second, second_sprite) in &sprites[i + 1..] {
+            if first_sprite.page == second_sprite.page
+                && first_sprite.rect != second_sprite.rect
+                && first_sprite
+                    .rect
+                    .to_u64()
+                    .intersects(&second_sprite.rect.to_u64())
+            {
+                problems.push(Problem::Overlap {
+                    first: first.to_path_buf(),
+                    first_rect: first_sprite.rect,
+                    second: second.to_path_buf(),
+                    second_rect: second_sprite.rect,
+                    page: first_sprite.page,
+                });
+            }
+        }
+    }
+
+    let mut animations = sprite_sheet.animations.iter().collect::<Vec<_>>();
+    animations.sort_by_key(|(name, _)| *name);
+    for (name, animation) in animations {
+        for frame in &animation.frames {
+            if !sprite_sheet.sprites.contains_key(&frame.sprite) {
+                problems.push(Problem::MissingFrame {
+                    animation: name.clone(),
+                    sprite: frame.sprite.clone(),
+                });
+            }
+        }
+    }
+
+    if let Some(cache) = Cache::load(path).await {
+        for input in cache.inputs() {
+            if !input.exists() {
+                problems.push(Problem::MissingSource { path: input });
+            }
+        }
+    }
+    else {
+        log::debug!(
+            "no cache for `{}`, not checking source files",
+            path.display()
+        );
+    }
+
+    Ok(problems)
+}
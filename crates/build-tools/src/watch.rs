@@ -0,0 +1,154 @@
+//! watches input files for changes, so that we can rebuild assets while the
+//! game is running.
+
+use std::{
+    collections::BTreeSet,
+    path::{
+        Component,
+        Path,
+        PathBuf,
+    },
+    time::Duration,
+};
+
+use notify::{
+    Config,
+    Event,
+    EventKind,
+    RecommendedWatcher,
+    RecursiveMode,
+    Watcher as _,
+};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Notify(#[from] notify::Error),
+}
+
+pub struct Watcher {
+    /// the file system watcher. it stops watching when it's dropped.
+    watcher: RecommendedWatcher,
+
+    /// paths that are watched.
+    watched: BTreeSet<PathBuf>,
+
+    /// receives the paths of changed files from the watcher.
+    changes: mpsc::UnboundedReceiver<PathBuf>,
+}
+
+impl Watcher {
+    /// how long there must be no changes before we report them. saving a file
+    /// often triggers multiple events.
+    pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+    pub fn new() -> Result<Self, Error> {
+        let (tx, changes) = mpsc::unbounded_channel();
+
+        let watcher = RecommendedWatcher::new(
+            move |event: notify::Result<Event>| {
+                match event {
+                    Ok(event) => {
+                        if !matches!(event.kind, EventKind::Access(_)) {
+                            for path in event.paths {
+                                tx.send(path).ok();
+                            }
+                        }
+                    }
+                    Err(e) => log::error!("watch error: {}", e),
+                }
+            },
+            Config::default(),
+        )?;
+
+        Ok(Self {
+            watcher,
+            watched: BTreeSet::new(),
+            changes,
+        })
+    }
+
+    /// watches `path` recursively, if it isn't watched yet. paths are
+    /// canonicalized, so they can be compared with the changed paths. paths
+    /// that don't exist can't be watched and are skipped.
+    pub fn watch(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if !path.exists() {
+            log::debug!("can't watch `{}`, because it doesn't exist", path.display());
+            return Ok(());
+        }
+        let path = path.canonicalize()?;
+
+        if !self.watched.contains(&path) {
+            log::debug!("watching `{}`", path.display());
+            self.watcher.watch(&path, RecursiveMode::Recursive)?;
+            self.watched.insert(path);
+        }
+
+        Ok(())
+    }
+
+    /// waits until something changed, and returns the changed paths.
+    pub async fn changes(&mut self) -> BTreeSet<PathBuf> {
+        let mut changes = BTreeSet::new();
+
+        if let Some(path) = self.changes.recv().await {
+            changes.insert(path);
+        }
+
+        while let Ok(Some(path)) = tokio::time::timeout(Self::DEBOUNCE, self.changes.recv()).await {
+            changes.insert(path);
+        }
+
+        for path in &changes {
+            log::debug!("`{}` changed", path.display());
+        }
+
+        changes
+    }
+}
+
+/// the directory that needs to be watched for changes to files that match a
+/// glob pattern. this is the longest prefix that doesn't contain any glob
+/// characters. for plain paths it's the parent directory.
+pub fn pattern_dir(pattern: &Path) -> PathBuf {
+    let mut dir = PathBuf::new();
+    let mut is_glob = false;
+
+    for component in pattern.components() {
+        if let Component::Normal(name) = component {
+            if name.to_string_lossy().contains(['*', '?', '[']) {
+                is_glob = true;
+                break;
+            }
+        }
+        dir.push(component);
+    }
+
+    if !is_glob {
+        dir.pop();
+    }
+
+    if dir.as_os_str().is_empty() {
+        PathBuf::from(".")
+    }
+    else {
+        dir
+    }
+}
+
+/// whether any of the `changes` is in one of the `dirs`. the dirs are
+/// canonicalized, since the changes are reported with canonical paths.
+pub fn any_changed<'a>(
+    changes: &BTreeSet<PathBuf>,
+    dirs: impl IntoIterator<Item = &'a PathBuf>,
+) -> bool {
+    dirs.into_iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| changes.iter().any(|path| path.starts_with(&dir)))
+}
@@ -14,6 +14,22 @@ pub enum Error {
 
     #[error("json error")]
     Json(#[from] serde_json::Error),
+
+    #[error("image error")]
+    Image(#[from] image::ImageError),
+
+    #[error("sprite sheet error")]
+    SpriteSheet(#[from] assets::sprite_sheet::DecodeError),
+
+    #[cfg(target_arch = "wasm32")]
+    #[error("failed to fetch `{url}`: http status {status}")]
+    Fetch { url: String, status: u16 },
+
+    #[error("texture `{label}` has no mip levels")]
+    NoMipLevels { label: String },
+
+    #[error("sprite `{name}` is not in the sprite sheet")]
+    MissingSprite { name: &'static str },
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -1,3 +1,4 @@
+use assets::sprite_sheet::SpriteId;
 use nalgebra::{
     Isometry3,
     Perspective3,
@@ -120,13 +121,18 @@ impl Camera {
 /// component that hides entities.
 pub struct Hidden;
 
-/// todo: something better than a plain index would be nice.
+/// a sprite from the global sprite sheet. use the constants in
+/// [`crate::sprites`] as ids.
 pub struct Sprite {
-    sprite_index: usize,
+    id: SpriteId,
 }
 
 impl Sprite {
-    pub fn new(sprite_index: usize) -> Self {
-        Self { sprite_index }
+    pub fn new(id: SpriteId) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> SpriteId {
+        self.id
     }
 }
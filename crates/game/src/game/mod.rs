@@ -47,6 +47,7 @@ use crate::{
         },
     },
     graphics::Graphics,
+    sprites,
 };
 
 pub struct Game {
@@ -133,7 +134,7 @@ impl Game {
         let model_entity = world.push((
             Dimension::OuterWorld,
             LocalTransform::default(),
-            Sprite::new(0),
+            Sprite::new(sprites::EXPLODING_HEAD_PIXELART),
         ));
         let camera_entity = world.push((
             Dimension::OuterWorld,
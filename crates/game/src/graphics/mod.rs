@@ -1,5 +1,10 @@
+pub mod sprite_sheet;
 pub mod texture;
 
+use std::path::Path;
+
+use assets::sprite_sheet::Sprite;
+use euclid::default::Size2D;
 use nalgebra::Matrix4;
 use wgpu::{
     include_wgsl,
@@ -16,9 +21,22 @@ use winit::{
 
 use crate::{
     error::Error,
-    graphics::texture::Texture,
+    graphics::sprite_sheet::LoadedSpriteSheet,
+    sprites,
 };
 
+/// the sprite sheet with all sprites, as built by `build-tools build`.
+#[cfg(not(target_arch = "wasm32"))]
+const SPRITE_SHEET: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../assets/atlases/sprites.json"
+);
+
+/// the sprite sheet with all sprites, relative to the page. trunk copies the
+/// `assets` directory next to it.
+#[cfg(target_arch = "wasm32")]
+const SPRITE_SHEET: &str = "assets/atlases/sprites.json";
+
 /// game state.
 ///
 /// todo: some things are pub because events are handled in `Game` right now. we
@@ -52,13 +70,11 @@ pub struct Graphics {
     /// number of indices in the index buffer.
     num_indices: u32,
 
-    /// bind group for the diffuse sprite sheet texture.
+    /// bind group for the sprite sheet page of the sprite that we render.
     diffuse_bind_group: wgpu::BindGroup,
 
-    /// the single diffuse texture that we render right now.
-    /// this will be the diffuse sprite sheet later, and sprite components refer
-    /// to a portion of it.
-    diffuse_texture: Texture,
+    /// the sprite sheet. sprite components refer to a portion of it.
+    sprite_sheet: LoadedSpriteSheet,
 }
 
 impl Graphics {
@@ -113,17 +129,21 @@ impl Graphics {
         // load shaders
         let shader = device.create_shader_module(&include_wgsl!("shader.wgsl"));
 
-        // load image
-        // todo: use a sprite sheet and use browser image decoding.
-        // todo: generate sprite sheet with build script, or a proc-macro.
-        let diffuse_texture_bytes =
-            include_bytes!("../../../../assets/sprites/exploding_head_pixelart.png");
-        let diffuse_texture = Texture::from_bytes(
-            &device,
-            &queue,
-            diffuse_texture_bytes,
-            "diffuse_sprite_sheet",
-        )?;
+        // load the sprite sheet with the mip levels of its pages.
+        // todo: use browser image decoding.
+        let sprite_sheet =
+            LoadedSpriteSheet::load(&device, &queue, Path::new(SPRITE_SHEET)).await?;
+
+        // todo: render the sprite components instead of a single sprite.
+        let sprite = sprite_sheet
+            .sprite_sheet
+            .sprite(sprites::EXPLODING_HEAD_PIXELART)
+            .ok_or_else(|| {
+                Error::MissingSprite {
+                    name: sprites::EXPLODING_HEAD_PIXELART.name(),
+                }
+            })?;
+        let diffuse_texture = &sprite_sheet.pages[sprite.page];
 
         let texture_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -189,7 +209,7 @@ impl Graphics {
                 targets: &[wgpu::ColorTargetState {
                     // 4.
                     format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
+                    blend: Some(sprite_sheet.blend_state()),
                     write_mask: wgpu::ColorWrites::ALL,
                 }],
             }),
@@ -214,9 +234,12 @@ impl Graphics {
             multiview: None,
         });
 
+        let page_size = Size2D::new(diffuse_texture.size.width, diffuse_texture.size.height);
+        let vertices = sprite_vertices(sprite, page_size);
+
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(VERTICES),
+            contents: bytemuck::cast_slice(&vertices),
             usage: wgpu::BufferUsages::VERTEX,
         });
 
@@ -238,7 +261,7 @@ impl Graphics {
             index_buffer,
             num_indices,
             diffuse_bind_group,
-            diffuse_texture,
+            sprite_sheet,
         })
     }
 
@@ -315,25 +338,27 @@ impl Vertex {
     }
 }
 
-const VERTICES: &[Vertex] = &[
-    Vertex {
-        position: [-0.5, 0.5, 0.0],
-        tex_coords: [0.0, 0.0],
-    },
-    Vertex {
-        position: [-0.5, -0.5, 0.0],
-        tex_coords: [0.0, 1.0],
-    },
-    Vertex {
-        position: [0.5, -0.5, 0.0],
-        tex_coords: [1.0, 1.0],
-    },
-    Vertex {
-        position: [0.5, 0.5, 0.0],
-        tex_coords: [1.0, 0.0],
-    },
+/// corners of the quad, in the same order as [`Sprite::tex_coords`].
+const POSITIONS: [[f32; 3]; 4] = [
+    [-0.5, 0.5, 0.0],
+    [-0.5, -0.5, 0.0],
+    [0.5, -0.5, 0.0],
+    [0.5, 0.5, 0.0],
 ];
 
+/// vertices of a quad that shows `sprite`. rotated sprites are turned upright
+/// by their texture coordinates.
+fn sprite_vertices(sprite: &Sprite, page_size: Size2D<u32>) -> [Vertex; 4] {
+    let tex_coords = sprite.tex_coords(page_size);
+
+    std::array::from_fn(|i| {
+        Vertex {
+            position: POSITIONS[i],
+            tex_coords: tex_coords[i],
+        }
+    })
+}
+
 const INDICES: &[u16] = &[0, 1, 3, 1, 2, 3];
 
 #[rustfmt::skip]
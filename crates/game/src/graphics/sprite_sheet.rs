@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use assets::sprite_sheet::SpriteSheet;
+use image::RgbaImage;
+
+use crate::{
+    error::Error,
+    graphics::texture::Texture,
+};
+
+/// a sprite sheet and a texture for each of its pages.
+#[derive(Debug)]
+pub struct LoadedSpriteSheet {
+    pub sprite_sheet: SpriteSheet,
+
+    /// the pages with all of their mip levels, in the same order as
+    /// [`SpriteSheet::pages`].
+    pub pages: Vec<Texture>,
+}
+
+impl LoadedSpriteSheet {
+    /// loads a sprite sheet, as written by `build-tools`, either as json or in
+    /// the binary format. every page is uploaded together with its mip levels.
+    ///
+    /// on wasm `path` is fetched relative to the page.
+    pub async fn load(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &Path,
+    ) -> Result<Self, Error> {
+        log::debug!("loading sprite sheet: {}", path.display());
+
+        let data = read(path).await?;
+        let sprite_sheet = if SpriteSheet::is_binary(&data) {
+            SpriteSheet::from_binary(&data)?
+        }
+        else {
+            serde_json::from_slice(&data)?
+        };
+
+        // page paths are relative to the sprite sheet.
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut pages = Vec::with_capacity(sprite_sheet.pages.len());
+        for (index, page) in sprite_sheet.pages.iter().enumerate() {
+            let mut levels = vec![load_image(&dir.join(page)).await?];
+            for level in sprite_sheet.mip_levels.get(index).into_iter().flatten() {
+                levels.push(load_image(&dir.join(level)).await?);
+            }
+
+            let label = format!("sprite_sheet_page_{}", index);
+            pages.push(Texture::from_mip_levels(device, queue, &levels, &label)?);
+        }
+
+        Ok(Self {
+            sprite_sheet,
+            pages,
+        })
+    }
+
+    /// how sprites from this sheet are blended onto the frame.
+    pub fn blend_state(&self) -> wgpu::BlendState {
+        if self.sprite_sheet.premultiplied_alpha {
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+        }
+        else {
+            wgpu::BlendState::ALPHA_BLENDING
+        }
+    }
+}
+
+async fn load_image(path: &Path) -> Result<RgbaImage, Error> {
+    let data = read(path).await?;
+    Ok(image::load_from_memory(&data)?.into_rgba8())
+}
+
+/// reads a file from disk, or fetches it on wasm.
+async fn read(path: &Path) -> Result<Vec<u8>, Error> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let url = path.to_string_lossy();
+        let response = JsFuture::from(web_sys::window().unwrap().fetch_with_str(&url))
+            .await?
+            .dyn_into::<web_sys::Response>()?;
+        if !response.ok() {
+            return Err(Error::Fetch {
+                url: url.into_owned(),
+                status: response.status(),
+            });
+        }
+
+        let buffer = JsFuture::from(response.array_buffer()?).await?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Ok(tokio::fs::read(path).await?)
+    }
+}
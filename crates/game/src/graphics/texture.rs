@@ -1,5 +1,3 @@
-use image::GenericImageView;
-
 use crate::error::Error;
 
 #[derive(Debug)]
@@ -7,6 +5,7 @@ pub struct Texture {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
+    pub size: wgpu::Extent3d,
 }
 
 impl Texture {
@@ -26,8 +25,27 @@ impl Texture {
         image: &image::DynamicImage,
         label: &str,
     ) -> Result<Self, Error> {
-        let diffuse_rgba = image.to_rgba8();
-        let dimensions = image.dimensions();
+        Self::from_mip_levels(device, queue, &[image.to_rgba8()], label)
+    }
+
+    /// creates a texture from a full mip chain, e.g. an atlas page and the mip
+    /// levels written by the build tools. `levels[0]` is the full size image,
+    /// every following level is half the size of the previous one. fails if
+    /// `levels` is empty.
+    pub fn from_mip_levels(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        levels: &[image::RgbaImage],
+        label: &str,
+    ) -> Result<Self, Error> {
+        let dimensions = levels
+            .first()
+            .ok_or_else(|| {
+                Error::NoMipLevels {
+                    label: label.to_owned(),
+                }
+            })?
+            .dimensions();
 
         let texture_size = wgpu::Extent3d {
             width: dimensions.0,
@@ -37,32 +55,40 @@ impl Texture {
 
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             size: texture_size,
-            mip_level_count: 1,
+            mip_level_count: levels.len() as u32,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            label: Some("diffuse_texture"),
+            label: Some(label),
         });
 
-        queue.write_texture(
-            // Tells wgpu where to copy the pixel data
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            // The actual pixel data
-            &diffuse_rgba,
-            // The layout of the texture
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(4 * dimensions.0),
-                rows_per_image: std::num::NonZeroU32::new(dimensions.1),
-            },
-            texture_size,
-        );
+        for (mip_level, level) in levels.iter().enumerate() {
+            let (width, height) = level.dimensions();
+
+            queue.write_texture(
+                // Tells wgpu where to copy the pixel data
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: mip_level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                // The actual pixel data
+                level,
+                // The layout of the texture
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(4 * width),
+                    rows_per_image: std::num::NonZeroU32::new(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
@@ -80,6 +106,7 @@ impl Texture {
             texture,
             view,
             sampler,
+            size: texture_size,
         })
     }
 }
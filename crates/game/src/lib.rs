@@ -8,6 +8,7 @@ pub mod game;
 pub mod graphics;
 pub mod inputs;
 pub mod sounds;
+pub mod sprites;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::wasm_bindgen;
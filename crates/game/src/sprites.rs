@@ -0,0 +1,7 @@
+//! typed ids for the sprites in `assets/atlases/sprites.json`.
+//!
+//! the constants in `sprites/ids.rs` are generated by `build-tools build`
+//! together with the atlas, so a sprite that was removed or renamed is a
+//! compile error. commit the module whenever it changes.
+
+include!("sprites/ids.rs");
@@ -0,0 +1,453 @@
+// sprite ids for `assets/atlases/sprites.json`. generated by build-tools, don't edit.
+
+/// `emotes/emote_alert.png`
+pub const EMOTES_EMOTE_ALERT: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_alert.png");
+
+/// `emotes/emote_anger.png`
+pub const EMOTES_EMOTE_ANGER: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_anger.png");
+
+/// `emotes/emote_bars.png`
+pub const EMOTES_EMOTE_BARS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_bars.png");
+
+/// `emotes/emote_bubble.png`
+pub const EMOTES_EMOTE_BUBBLE: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_bubble.png");
+
+/// `emotes/emote_cash.png`
+pub const EMOTES_EMOTE_CASH: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_cash.png");
+
+/// `emotes/emote_circle.png`
+pub const EMOTES_EMOTE_CIRCLE: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_circle.png");
+
+/// `emotes/emote_cloud.png`
+pub const EMOTES_EMOTE_CLOUD: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_cloud.png");
+
+/// `emotes/emote_cross.png`
+pub const EMOTES_EMOTE_CROSS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_cross.png");
+
+/// `emotes/emote_dots1.png`
+pub const EMOTES_EMOTE_DOTS1: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_dots1.png");
+
+/// `emotes/emote_dots2.png`
+pub const EMOTES_EMOTE_DOTS2: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_dots2.png");
+
+/// `emotes/emote_dots3.png`
+pub const EMOTES_EMOTE_DOTS3: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_dots3.png");
+
+/// `emotes/emote_drop.png`
+pub const EMOTES_EMOTE_DROP: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_drop.png");
+
+/// `emotes/emote_drops.png`
+pub const EMOTES_EMOTE_DROPS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_drops.png");
+
+/// `emotes/emote_exclamation.png`
+pub const EMOTES_EMOTE_EXCLAMATION: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_exclamation.png");
+
+/// `emotes/emote_exclamations.png`
+pub const EMOTES_EMOTE_EXCLAMATIONS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_exclamations.png");
+
+/// `emotes/emote_faceAngry.png`
+pub const EMOTES_EMOTE_FACE_ANGRY: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_faceAngry.png");
+
+/// `emotes/emote_faceHappy.png`
+pub const EMOTES_EMOTE_FACE_HAPPY: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_faceHappy.png");
+
+/// `emotes/emote_faceSad.png`
+pub const EMOTES_EMOTE_FACE_SAD: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_faceSad.png");
+
+/// `emotes/emote_heart.png`
+pub const EMOTES_EMOTE_HEART: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_heart.png");
+
+/// `emotes/emote_hearts.png`
+pub const EMOTES_EMOTE_HEARTS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_hearts.png");
+
+/// `emotes/emote_heartBroken.png`
+pub const EMOTES_EMOTE_HEART_BROKEN: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_heartBroken.png");
+
+/// `emotes/emote_idea.png`
+pub const EMOTES_EMOTE_IDEA: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_idea.png");
+
+/// `emotes/emote_laugh.png`
+pub const EMOTES_EMOTE_LAUGH: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_laugh.png");
+
+/// `emotes/emote_music.png`
+pub const EMOTES_EMOTE_MUSIC: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_music.png");
+
+/// `emotes/emote_question.png`
+pub const EMOTES_EMOTE_QUESTION: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_question.png");
+
+/// `emotes/emote_sleep.png`
+pub const EMOTES_EMOTE_SLEEP: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_sleep.png");
+
+/// `emotes/emote_sleeps.png`
+pub const EMOTES_EMOTE_SLEEPS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_sleeps.png");
+
+/// `emotes/emote_star.png`
+pub const EMOTES_EMOTE_STAR: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_star.png");
+
+/// `emotes/emote_stars.png`
+pub const EMOTES_EMOTE_STARS: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_stars.png");
+
+/// `emotes/emote_swirl.png`
+pub const EMOTES_EMOTE_SWIRL: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("emotes/emote_swirl.png");
+
+/// `exploding_head_pixelart.aseprite`
+pub const EXPLODING_HEAD_PIXELART: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("exploding_head_pixelart.aseprite");
+
+/// `particles/circle_01.png`
+pub const PARTICLES_CIRCLE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/circle_01.png");
+
+/// `particles/circle_02.png`
+pub const PARTICLES_CIRCLE_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/circle_02.png");
+
+/// `particles/circle_03.png`
+pub const PARTICLES_CIRCLE_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/circle_03.png");
+
+/// `particles/circle_04.png`
+pub const PARTICLES_CIRCLE_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/circle_04.png");
+
+/// `particles/circle_05.png`
+pub const PARTICLES_CIRCLE_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/circle_05.png");
+
+/// `particles/dirt_01.png`
+pub const PARTICLES_DIRT_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/dirt_01.png");
+
+/// `particles/dirt_02.png`
+pub const PARTICLES_DIRT_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/dirt_02.png");
+
+/// `particles/dirt_03.png`
+pub const PARTICLES_DIRT_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/dirt_03.png");
+
+/// `particles/fire_01.png`
+pub const PARTICLES_FIRE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/fire_01.png");
+
+/// `particles/fire_02.png`
+pub const PARTICLES_FIRE_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/fire_02.png");
+
+/// `particles/flame_01.png`
+pub const PARTICLES_FLAME_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_01.png");
+
+/// `particles/flame_02.png`
+pub const PARTICLES_FLAME_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_02.png");
+
+/// `particles/flame_03.png`
+pub const PARTICLES_FLAME_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_03.png");
+
+/// `particles/flame_04.png`
+pub const PARTICLES_FLAME_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_04.png");
+
+/// `particles/flame_05.png`
+pub const PARTICLES_FLAME_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_05.png");
+
+/// `particles/flame_06.png`
+pub const PARTICLES_FLAME_06: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flame_06.png");
+
+/// `particles/flare_01.png`
+pub const PARTICLES_FLARE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/flare_01.png");
+
+/// `particles/light_01.png`
+pub const PARTICLES_LIGHT_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/light_01.png");
+
+/// `particles/light_02.png`
+pub const PARTICLES_LIGHT_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/light_02.png");
+
+/// `particles/light_03.png`
+pub const PARTICLES_LIGHT_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/light_03.png");
+
+/// `particles/magic_01.png`
+pub const PARTICLES_MAGIC_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/magic_01.png");
+
+/// `particles/magic_02.png`
+pub const PARTICLES_MAGIC_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/magic_02.png");
+
+/// `particles/magic_03.png`
+pub const PARTICLES_MAGIC_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/magic_03.png");
+
+/// `particles/magic_04.png`
+pub const PARTICLES_MAGIC_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/magic_04.png");
+
+/// `particles/magic_05.png`
+pub const PARTICLES_MAGIC_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/magic_05.png");
+
+/// `particles/muzzle_01.png`
+pub const PARTICLES_MUZZLE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/muzzle_01.png");
+
+/// `particles/muzzle_02.png`
+pub const PARTICLES_MUZZLE_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/muzzle_02.png");
+
+/// `particles/muzzle_03.png`
+pub const PARTICLES_MUZZLE_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/muzzle_03.png");
+
+/// `particles/muzzle_04.png`
+pub const PARTICLES_MUZZLE_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/muzzle_04.png");
+
+/// `particles/muzzle_05.png`
+pub const PARTICLES_MUZZLE_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/muzzle_05.png");
+
+/// `particles/scorch_01.png`
+pub const PARTICLES_SCORCH_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/scorch_01.png");
+
+/// `particles/scorch_02.png`
+pub const PARTICLES_SCORCH_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/scorch_02.png");
+
+/// `particles/scorch_03.png`
+pub const PARTICLES_SCORCH_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/scorch_03.png");
+
+/// `particles/scratch_01.png`
+pub const PARTICLES_SCRATCH_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/scratch_01.png");
+
+/// `particles/slash_01.png`
+pub const PARTICLES_SLASH_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/slash_01.png");
+
+/// `particles/slash_02.png`
+pub const PARTICLES_SLASH_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/slash_02.png");
+
+/// `particles/slash_03.png`
+pub const PARTICLES_SLASH_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/slash_03.png");
+
+/// `particles/slash_04.png`
+pub const PARTICLES_SLASH_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/slash_04.png");
+
+/// `particles/smoke_01.png`
+pub const PARTICLES_SMOKE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_01.png");
+
+/// `particles/smoke_02.png`
+pub const PARTICLES_SMOKE_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_02.png");
+
+/// `particles/smoke_03.png`
+pub const PARTICLES_SMOKE_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_03.png");
+
+/// `particles/smoke_04.png`
+pub const PARTICLES_SMOKE_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_04.png");
+
+/// `particles/smoke_05.png`
+pub const PARTICLES_SMOKE_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_05.png");
+
+/// `particles/smoke_06.png`
+pub const PARTICLES_SMOKE_06: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_06.png");
+
+/// `particles/smoke_07.png`
+pub const PARTICLES_SMOKE_07: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_07.png");
+
+/// `particles/smoke_08.png`
+pub const PARTICLES_SMOKE_08: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_08.png");
+
+/// `particles/smoke_09.png`
+pub const PARTICLES_SMOKE_09: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_09.png");
+
+/// `particles/smoke_10.png`
+pub const PARTICLES_SMOKE_10: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/smoke_10.png");
+
+/// `particles/spark_01.png`
+pub const PARTICLES_SPARK_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_01.png");
+
+/// `particles/spark_02.png`
+pub const PARTICLES_SPARK_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_02.png");
+
+/// `particles/spark_03.png`
+pub const PARTICLES_SPARK_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_03.png");
+
+/// `particles/spark_04.png`
+pub const PARTICLES_SPARK_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_04.png");
+
+/// `particles/spark_05.png`
+pub const PARTICLES_SPARK_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_05.png");
+
+/// `particles/spark_06.png`
+pub const PARTICLES_SPARK_06: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_06.png");
+
+/// `particles/spark_07.png`
+pub const PARTICLES_SPARK_07: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/spark_07.png");
+
+/// `particles/star_01.png`
+pub const PARTICLES_STAR_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_01.png");
+
+/// `particles/star_02.png`
+pub const PARTICLES_STAR_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_02.png");
+
+/// `particles/star_03.png`
+pub const PARTICLES_STAR_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_03.png");
+
+/// `particles/star_04.png`
+pub const PARTICLES_STAR_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_04.png");
+
+/// `particles/star_05.png`
+pub const PARTICLES_STAR_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_05.png");
+
+/// `particles/star_06.png`
+pub const PARTICLES_STAR_06: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_06.png");
+
+/// `particles/star_07.png`
+pub const PARTICLES_STAR_07: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_07.png");
+
+/// `particles/star_08.png`
+pub const PARTICLES_STAR_08: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_08.png");
+
+/// `particles/star_09.png`
+pub const PARTICLES_STAR_09: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/star_09.png");
+
+/// `particles/symbol_01.png`
+pub const PARTICLES_SYMBOL_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/symbol_01.png");
+
+/// `particles/symbol_02.png`
+pub const PARTICLES_SYMBOL_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/symbol_02.png");
+
+/// `particles/trace_01.png`
+pub const PARTICLES_TRACE_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_01.png");
+
+/// `particles/trace_02.png`
+pub const PARTICLES_TRACE_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_02.png");
+
+/// `particles/trace_03.png`
+pub const PARTICLES_TRACE_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_03.png");
+
+/// `particles/trace_04.png`
+pub const PARTICLES_TRACE_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_04.png");
+
+/// `particles/trace_05.png`
+pub const PARTICLES_TRACE_05: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_05.png");
+
+/// `particles/trace_06.png`
+pub const PARTICLES_TRACE_06: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_06.png");
+
+/// `particles/trace_07.png`
+pub const PARTICLES_TRACE_07: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/trace_07.png");
+
+/// `particles/twirl_01.png`
+pub const PARTICLES_TWIRL_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/twirl_01.png");
+
+/// `particles/twirl_02.png`
+pub const PARTICLES_TWIRL_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/twirl_02.png");
+
+/// `particles/twirl_03.png`
+pub const PARTICLES_TWIRL_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/twirl_03.png");
+
+/// `particles/window_01.png`
+pub const PARTICLES_WINDOW_01: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/window_01.png");
+
+/// `particles/window_02.png`
+pub const PARTICLES_WINDOW_02: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/window_02.png");
+
+/// `particles/window_03.png`
+pub const PARTICLES_WINDOW_03: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/window_03.png");
+
+/// `particles/window_04.png`
+pub const PARTICLES_WINDOW_04: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("particles/window_04.png");
+
+/// `plurality_pixelart.aseprite`
+pub const PLURALITY_PIXELART: assets::sprite_sheet::SpriteId = assets::sprite_sheet::SpriteId::new("plurality_pixelart.aseprite");
+
+/// all sprites in the sprite sheet.
+pub const ALL: [assets::sprite_sheet::SpriteId; 112] = [
+    EMOTES_EMOTE_ALERT,
+    EMOTES_EMOTE_ANGER,
+    EMOTES_EMOTE_BARS,
+    EMOTES_EMOTE_BUBBLE,
+    EMOTES_EMOTE_CASH,
+    EMOTES_EMOTE_CIRCLE,
+    EMOTES_EMOTE_CLOUD,
+    EMOTES_EMOTE_CROSS,
+    EMOTES_EMOTE_DOTS1,
+    EMOTES_EMOTE_DOTS2,
+    EMOTES_EMOTE_DOTS3,
+    EMOTES_EMOTE_DROP,
+    EMOTES_EMOTE_DROPS,
+    EMOTES_EMOTE_EXCLAMATION,
+    EMOTES_EMOTE_EXCLAMATIONS,
+    EMOTES_EMOTE_FACE_ANGRY,
+    EMOTES_EMOTE_FACE_HAPPY,
+    EMOTES_EMOTE_FACE_SAD,
+    EMOTES_EMOTE_HEART,
+    EMOTES_EMOTE_HEARTS,
+    EMOTES_EMOTE_HEART_BROKEN,
+    EMOTES_EMOTE_IDEA,
+    EMOTES_EMOTE_LAUGH,
+    EMOTES_EMOTE_MUSIC,
+    EMOTES_EMOTE_QUESTION,
+    EMOTES_EMOTE_SLEEP,
+    EMOTES_EMOTE_SLEEPS,
+    EMOTES_EMOTE_STAR,
+    EMOTES_EMOTE_STARS,
+    EMOTES_EMOTE_SWIRL,
+    EXPLODING_HEAD_PIXELART,
+    PARTICLES_CIRCLE_01,
+    PARTICLES_CIRCLE_02,
+    PARTICLES_CIRCLE_03,
+    PARTICLES_CIRCLE_04,
+    PARTICLES_CIRCLE_05,
+    PARTICLES_DIRT_01,
+    PARTICLES_DIRT_02,
+    PARTICLES_DIRT_03,
+    PARTICLES_FIRE_01,
+    PARTICLES_FIRE_02,
+    PARTICLES_FLAME_01,
+    PARTICLES_FLAME_02,
+    PARTICLES_FLAME_03,
+    PARTICLES_FLAME_04,
+    PARTICLES_FLAME_05,
+    PARTICLES_FLAME_06,
+    PARTICLES_FLARE_01,
+    PARTICLES_LIGHT_01,
+    PARTICLES_LIGHT_02,
+    PARTICLES_LIGHT_03,
+    PARTICLES_MAGIC_01,
+    PARTICLES_MAGIC_02,
+    PARTICLES_MAGIC_03,
+    PARTICLES_MAGIC_04,
+    PARTICLES_MAGIC_05,
+    PARTICLES_MUZZLE_01,
+    PARTICLES_MUZZLE_02,
+    PARTICLES_MUZZLE_03,
+    PARTICLES_MUZZLE_04,
+    PARTICLES_MUZZLE_05,
+    PARTICLES_SCORCH_01,
+    PARTICLES_SCORCH_02,
+    PARTICLES_SCORCH_03,
+    PARTICLES_SCRATCH_01,
+    PARTICLES_SLASH_01,
+    PARTICLES_SLASH_02,
+    PARTICLES_SLASH_03,
+    PARTICLES_SLASH_04,
+    PARTICLES_SMOKE_01,
+    PARTICLES_SMOKE_02,
+    PARTICLES_SMOKE_03,
+    PARTICLES_SMOKE_04,
+    PARTICLES_SMOKE_05,
+    PARTICLES_SMOKE_06,
+    PARTICLES_SMOKE_07,
+    PARTICLES_SMOKE_08,
+    PARTICLES_SMOKE_09,
+    PARTICLES_SMOKE_10,
+    PARTICLES_SPARK_01,
+    PARTICLES_SPARK_02,
+    PARTICLES_SPARK_03,
+    PARTICLES_SPARK_04,
+    PARTICLES_SPARK_05,
+    PARTICLES_SPARK_06,
+    PARTICLES_SPARK_07,
+    PARTICLES_STAR_01,
+    PARTICLES_STAR_02,
+    PARTICLES_STAR_03,
+    PARTICLES_STAR_04,
+    PARTICLES_STAR_05,
+    PARTICLES_STAR_06,
+    PARTICLES_STAR_07,
+    PARTICLES_STAR_08,
+    PARTICLES_STAR_09,
+    PARTICLES_SYMBOL_01,
+    PARTICLES_SYMBOL_02,
+    PARTICLES_TRACE_01,
+    PARTICLES_TRACE_02,
+    PARTICLES_TRACE_03,
+    PARTICLES_TRACE_04,
+    PARTICLES_TRACE_05,
+    PARTICLES_TRACE_06,
+    PARTICLES_TRACE_07,
+    PARTICLES_TWIRL_01,
+    PARTICLES_TWIRL_02,
+    PARTICLES_TWIRL_03,
+    PARTICLES_WINDOW_01,
+    PARTICLES_WINDOW_02,
+    PARTICLES_WINDOW_03,
+    PARTICLES_WINDOW_04,
+    PLURALITY_PIXELART,
+];
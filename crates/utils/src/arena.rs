@@ -18,8 +18,12 @@ pub struct Index {
 
 impl Index {
     fn new(index: usize) -> Self {
-        let index = u16::try_from(index).unwrap_or_else(|e| panic!("Index overflowed: {}", e));
-        let index = unsafe { NonZeroU16::new_unchecked(index + 1) };
+        // the index is stored plus one, so the last `u16` can't be used.
+        let index = u16::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_add(1))
+            .and_then(NonZeroU16::new)
+            .unwrap_or_else(|| panic!("Index overflowed: {}", index));
         Self { index }
     }
 
@@ -175,3 +179,362 @@ impl<'a, T> Iterator for IterMut<'a, T> {
             .map(|(index, item)| (Index::new(index), item))
     }
 }
+
+/// index into a [`GenerationalArena`]. besides the slot, this stores the
+/// generation of the slot when the item was inserted. once the item is removed
+/// and the slot is reused, the index is stale and doesn't refer to anything.
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GenerationalIndex {
+    index: Index,
+    generation: u32,
+}
+
+impl GenerationalIndex {
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Debug for GenerationalIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GenerationalIndex")
+            .field(&self.index.index)
+            .field(&self.generation)
+            .finish()
+    }
+}
+
+enum Slot<T> {
+    Occupied {
+        generation: u32,
+        item: T,
+    },
+    Free {
+        generation: u32,
+        next_free: Option<usize>,
+    },
+}
+
+/// like [`Arena`], but items can be removed, e.g. for runtime resources like
+/// textures and sounds that come and go.
+///
+/// removed slots are kept in a free list and reused by later inserts. every
+/// slot has a generation that is incremented when its item is removed, so
+/// [`GenerationalArena::get`] returns `None` for indices of removed items,
+/// even if the slot was reused.
+///
+/// unlike [`Arena`] this can't be serialized, since the free list and `len`
+/// would have to be validated when deserializing it.
+pub struct GenerationalArena<T> {
+    slots: Vec<Slot<T>>,
+    first_free: Option<usize>,
+    len: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenerationalArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for GenerationalArena<T> {
+    fn default() -> Self {
+        Self {
+            slots: vec![],
+            first_free: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> GenerationalArena<T> {
+    pub fn insert(&mut self, item: T) -> GenerationalIndex {
+        let index = if let Some(index) = self.first_free {
+            let slot = &mut self.slots[index];
+            let (generation, next_free) = match slot {
+                Slot::Free {
+                    generation,
+                    next_free,
+                } => (*generation, *next_free),
+                Slot::Occupied { .. } => unreachable!("occupied slot in free list"),
+            };
+            *slot = Slot::Occupied { generation, item };
+            self.first_free = next_free;
+
+            GenerationalIndex {
+                index: Index::new(index),
+                generation,
+            }
+        }
+        else {
+            // might panic, so nothing is changed before this.
+            let index = Index::new(self.slots.len());
+            self.slots.push(Slot::Occupied {
+                generation: 0,
+                item,
+            });
+
+            GenerationalIndex {
+                index,
+                generation: 0,
+            }
+        };
+
+        self.len += 1;
+        index
+    }
+
+    /// removes the item and returns it. returns `None` if the index is stale.
+    pub fn remove(&mut self, index: GenerationalIndex) -> Option<T> {
+        let slot = self.slots.get_mut(index.index.index())?;
+        match slot {
+            Slot::Occupied { generation, .. } if *generation == index.generation => {}
+            _ => return None,
+        }
+
+        // generations wrap around. an index would have to be kept around for
+        // 2^32 removals of the same slot to become valid again.
+        let free = Slot::Free {
+            generation: index.generation.wrapping_add(1),
+            next_free: self.first_free,
+        };
+        self.first_free = Some(index.index.index());
+        self.len -= 1;
+
+        match std::mem::replace(slot, free) {
+            Slot::Occupied { item, .. } => Some(item),
+            Slot::Free { .. } => unreachable!(),
+        }
+    }
+
+    pub fn get(&self, index: GenerationalIndex) -> Option<&T> {
+        match self.slots.get(index.index.index())? {
+            Slot::Occupied { generation, item } if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: GenerationalIndex) -> Option<&mut T> {
+        match self.slots.get_mut(index.index.index())? {
+            Slot::Occupied { generation, item } if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: GenerationalIndex) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// removes all items, for which `f` returns `false`.
+    pub fn retain<F: FnMut(GenerationalIndex, &mut T) -> bool>(&mut self, mut f: F) {
+        let remove = self
+            .iter_mut()
+            .filter_map(|(index, item)| (!f(index, item)).then_some(index))
+            .collect::<Vec<_>>();
+
+        for index in remove {
+            self.remove(index);
+        }
+    }
+
+    /// removes all items. indices of the removed items stay stale.
+    pub fn clear(&mut self) {
+        let indices = self.iter().map(|(index, _)| index).collect::<Vec<_>>();
+        for index in indices {
+            self.remove(index);
+        }
+    }
+
+    pub fn iter(&self) -> GenerationalIter<'_, T> {
+        self.into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> GenerationalIterMut<'_, T> {
+        self.into_iter()
+    }
+
+    /// number of items, not counting removed ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> std::ops::Index<GenerationalIndex> for GenerationalArena<T> {
+    type Output = T;
+
+    fn index(&self, index: GenerationalIndex) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("stale index: {:?}", index))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GenerationalArena<T> {
+    type Item = (GenerationalIndex, &'a T);
+    type IntoIter = GenerationalIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GenerationalIter {
+            inner: self.slots.iter().enumerate(),
+        }
+    }
+}
+
+pub struct GenerationalIter<'a, T> {
+    inner: Enumerate<std::slice::Iter<'a, Slot<T>>>,
+}
+
+impl<'a, T> Iterator for GenerationalIter<'a, T> {
+    type Item = (GenerationalIndex, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.find_map(|(index, slot)| {
+            match slot {
+                Slot::Occupied { generation, item } => {
+                    Some((
+                        GenerationalIndex {
+                            index: Index::new(index),
+                            generation: *generation,
+                        },
+                        item,
+                    ))
+                }
+                Slot::Free { .. } => None,
+            }
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut GenerationalArena<T> {
+    type Item = (GenerationalIndex, &'a mut T);
+    type IntoIter = GenerationalIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GenerationalIterMut {
+            inner: self.slots.iter_mut().enumerate(),
+        }
+    }
+}
+
+pub struct GenerationalIterMut<'a, T> {
+    inner: Enumerate<std::slice::IterMut<'a, Slot<T>>>,
+}
+
+impl<'a, T> Iterator for GenerationalIterMut<'a, T> {
+    type Item = (GenerationalIndex, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.find_map(|(index, slot)| {
+            match slot {
+                Slot::Occupied { generation, item } => {
+                    Some((
+                        GenerationalIndex {
+                            index: Index::new(index),
+                            generation: *generation,
+                        },
+                        item,
+                    ))
+                }
+                Slot::Free { .. } => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut arena = GenerationalArena::default();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.get(a), Some(&"a"));
+        assert_eq!(arena[b], "b");
+        assert_eq!(
+            arena.iter().map(|(_, item)| *item).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut arena = GenerationalArena::default();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+
+        assert_eq!(arena.remove(a), Some("a"));
+        assert_eq!(arena.remove(a), None);
+        assert_eq!(arena.len(), 1);
+        assert!(!arena.contains(a));
+        assert!(arena.contains(b));
+    }
+
+    #[test]
+    fn reuses_removed_slots() {
+        let mut arena = GenerationalArena::default();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+        arena.remove(a);
+        arena.remove(b);
+
+        // the free list is last in, first out.
+        let c = arena.insert("c");
+        let d = arena.insert("d");
+        assert_eq!(c.index, b.index);
+        assert_eq!(d.index, a.index);
+        assert_eq!(c.generation(), b.generation() + 1);
+        assert_eq!(arena.slots.len(), 2);
+        assert_eq!(arena.len(), 2);
+    }
+
+    #[test]
+    fn stale_indices_dont_find_reused_slots() {
+        let mut arena = GenerationalArena::default();
+        let a = arena.insert("a");
+        arena.remove(a);
+        let b = arena.insert("b");
+
+        assert_eq!(a.index, b.index);
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get_mut(a), None);
+        assert_eq!(arena.remove(a), None);
+        assert_eq!(arena.get(b), Some(&"b"));
+    }
+
+    #[test]
+    fn retain_and_clear() {
+        let mut arena = GenerationalArena::default();
+        let indices = (0..4).map(|i| arena.insert(i)).collect::<Vec<_>>();
+
+        arena.retain(|_, item| *item % 2 == 0);
+        assert_eq!(
+            arena.iter().map(|(_, item)| *item).collect::<Vec<_>>(),
+            [0, 2]
+        );
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert!(indices.iter().all(|index| !arena.contains(*index)));
+    }
+
+    #[test]
+    fn last_index() {
+        assert_eq!(
+            Index::new(u16::MAX as usize - 1).index(),
+            u16::MAX as usize - 1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Index overflowed")]
+    fn index_overflow() {
+        Index::new(u16::MAX as usize);
+    }
+}